@@ -1,8 +1,10 @@
 use tetra::Context;
-use tetra::graphics::{Texture, Rectangle, Shader};
+use tetra::graphics::{Texture, Rectangle, Shader, Color};
 use tetra::graphics::shader::DEFAULT_VERTEX_SHADER;
 use std::collections::HashMap;
 use crate::utils::animation::Animation;
+use crate::models::board::MAXNUMBER;
+use crate::constants::GET_VALUE_TINT;
 
 type TextureHashmap = HashMap<TextureName, Texture>;
 type AnimationHashmap = HashMap<AnimationName, Animation>;
@@ -70,6 +72,12 @@ fn build_textures(ctx: &mut Context) ->tetra::Result<TextureHashmap>{
 		(TextureName::JamLogo, Texture::from_file_data(ctx, include_bytes!("../assets/art/open-jam-logo.png"))?),
 		(TextureName::Logo, Texture::from_file_data(ctx, include_bytes!("../assets/art/logo.png"))?),
 		(TextureName::Thx, Texture::from_file_data(ctx, include_bytes!("../assets/art/thx.png"))?),
+		(TextureName::Wall, Texture::from_file_data(ctx, include_bytes!("../assets/art/wall.png"))?),
+		(TextureName::Undo, Texture::from_file_data(ctx, include_bytes!("../assets/art/button_undo.png"))?),
+		(TextureName::Redo, Texture::from_file_data(ctx, include_bytes!("../assets/art/button_redo.png"))?),
+		(TextureName::Highlight, Texture::from_file_data(ctx, include_bytes!("../assets/art/art_08.png"))?),
+		(TextureName::Hint, Texture::from_file_data(ctx, include_bytes!("../assets/art/button_hint.png"))?),
+		(TextureName::Star, Texture::from_file_data(ctx, include_bytes!("../assets/art/star.png"))?),
 		].iter().cloned().collect()
 	)
 }
@@ -78,6 +86,9 @@ fn build_animations(ctx: &mut Context) ->tetra::Result<AnimationHashmap>{
 	let tileset = Texture::from_file_data(ctx, include_bytes!("../assets/art/art.png"))?;
 	let button_tileset = Texture::from_file_data(ctx, include_bytes!("../assets/art/button.png"))?;
 	let symbol_tileset = Texture::from_file_data(ctx, include_bytes!("../assets/art/symbol.png"))?;
+	let undo_tileset = Texture::from_file_data(ctx, include_bytes!("../assets/art/button_undo_hover.png"))?;
+	let redo_tileset = Texture::from_file_data(ctx, include_bytes!("../assets/art/button_redo_hover.png"))?;
+	let hint_tileset = Texture::from_file_data(ctx, include_bytes!("../assets/art/button_hint_hover.png"))?;
 	Ok([
 		(AnimationName::Plus, Animation::new(tileset.clone(),Rectangle::row(0.0, 32.0, 16.0, 16.0).take(4).collect(), 5)),
 		(AnimationName::Minus, Animation::new(tileset.clone(),Rectangle::row(64.0, 32.0, 16.0, 16.0).take(4).collect(), 5)),
@@ -91,6 +102,9 @@ fn build_animations(ctx: &mut Context) ->tetra::Result<AnimationHashmap>{
 		(AnimationName::Back, Animation::new(button_tileset.clone(),Rectangle::row(0.0, 32.0, 32.0, 16.0).take(6).collect(), 6)),
 		(AnimationName::Next, Animation::new(button_tileset.clone(),Rectangle::row(0.0, 48.0, 32.0, 16.0).take(6).collect(), 6)),
 		(AnimationName::Action, Animation::new(symbol_tileset.clone(),Rectangle::row(0.0, 0.0, 8.0, 8.0).take(4).collect(), 6)),
+		(AnimationName::Undo, Animation::new(undo_tileset.clone(),Rectangle::row(0.0, 0.0, 16.0, 16.0).take(3).collect(), 6)),
+		(AnimationName::Redo, Animation::new(redo_tileset.clone(),Rectangle::row(0.0, 0.0, 16.0, 16.0).take(3).collect(), 6)),
+		(AnimationName::Hint, Animation::new(hint_tileset.clone(),Rectangle::row(0.0, 0.0, 16.0, 16.0).take(3).collect(), 6)),
 		].iter().cloned().collect()
 	)
 }
@@ -130,6 +144,12 @@ pub enum TextureName {
 	JamLogo,
 	Logo,
 	Thx,
+	Wall,
+	Undo,
+	Redo,
+	Highlight,
+	Hint,
+	Star,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -139,6 +159,9 @@ pub enum AnimationName {
 	Back,
 	Future,
 	Next,
+	Undo,
+	Redo,
+	Hint,
 	Action,
 	Tile43,
 	Tile32,
@@ -168,6 +191,16 @@ impl TextureName {
 			_ => TextureName::Pic0On,
 		}
 	}
+
+	// values above the drawn ones start over at Pic1 with a tint per round
+	pub fn for_value(value: u8, enabled: bool) -> (TextureName, Color) {
+		if value == 0 {
+			return (TextureName::from_str(&format!("Pic0{}", if enabled { "On" } else { "Off" })), GET_VALUE_TINT(0));
+		}
+		let band = (value - 1) / MAXNUMBER;
+		let picture = (value - 1) % MAXNUMBER + 1;
+		(TextureName::from_str(&format!("Pic{}{}", picture, if enabled { "On" } else { "Off" })), GET_VALUE_TINT(band))
+	}
 	/*
 	pub fn as_str(&self) -> &'static str {
 		match s {
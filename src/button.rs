@@ -23,11 +23,17 @@ impl Button {
 			ButtonType::Future => AnimationName::Future,
 			ButtonType::Back => AnimationName::Back,
 			ButtonType::Next => AnimationName::Next,
+			ButtonType::Undo => AnimationName::Undo,
+			ButtonType::Redo => AnimationName::Redo,
+			ButtonType::Hint => AnimationName::Hint,
 		};
 		let texture_name = match button_type{
 			ButtonType::Future => TextureName::Future,
 			ButtonType::Back => TextureName::Back,
 			ButtonType::Next => TextureName::Next,
+			ButtonType::Undo => TextureName::Undo,
+			ButtonType::Redo => TextureName::Redo,
+			ButtonType::Hint => TextureName::Hint,
 		};
 		Ok(Button{
 			assets,
@@ -45,11 +51,17 @@ impl Button {
 			ButtonType::Future => AnimationName::Future,
 			ButtonType::Back => AnimationName::Back,
 			ButtonType::Next => AnimationName::Next,
+			ButtonType::Undo => AnimationName::Undo,
+			ButtonType::Redo => AnimationName::Redo,
+			ButtonType::Hint => AnimationName::Hint,
 		};
 		self.texture_name = match button_type{
 			ButtonType::Future => TextureName::Future,
 			ButtonType::Back => TextureName::Back,
 			ButtonType::Next => TextureName::Next,
+			ButtonType::Undo => TextureName::Undo,
+			ButtonType::Redo => TextureName::Redo,
+			ButtonType::Hint => TextureName::Hint,
 		};
 	}
 
@@ -132,4 +144,7 @@ pub enum ButtonType {
 	Future,
 	Back,
 	Next,
+	Undo,
+	Redo,
+	Hint,
 }
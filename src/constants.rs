@@ -7,6 +7,12 @@ pub const TILE_SIZE: i32 = 16;
 pub const TILE_GAP: i32 = 1;
 pub const TOP_BORDER_SPACING: i32 = 20;
 pub const LEFT_BORDER_SPACING: i32 = 25;
+pub const BOARD_WIDTH: i32 = 90;
+pub const BOARD_HEIGHT: i32 = 58;
+
+// leaves room for the future button that turns the page
+pub const SPLITS_PER_COLUMN: usize = 5;
+pub const SPLITS_COLUMN_WIDTH: f32 = 48.0;
 
 pub const X_POSITION_MOVES_SYMBOLE: u8 = 36;
 pub const Y_POSITION_MOVES_SYMBOLE: u8 = 6;
@@ -16,6 +22,26 @@ pub fn GET_TOUCH_AREA_BUTTON() ->Rectangle{
 	Rectangle::new(0.0,0.0,32.0,16.0)
 }
 
+pub fn GET_TOUCH_AREA_SMALL_BUTTON() ->Rectangle{
+	Rectangle::new(0.0,0.0,16.0,16.0)
+}
+
+pub fn GET_POSITION_UNDO_BUTTON() -> Vec2{
+	Vec2::new(2.0,2.0)
+}
+
+pub fn GET_POSITION_REDO_BUTTON() -> Vec2{
+	Vec2::new(18.0,2.0)
+}
+
+pub fn GET_POSITION_HINT_BUTTON() -> Vec2{
+	Vec2::new(43.0,80.0)
+}
+
+pub fn GET_HINT_TEXT_POSITION() -> Vec2{
+	Vec2::new(4.0,70.0)
+}
+
 pub fn GET_POSITION_FUTURE_BUTTON() -> Vec2{
 	Vec2::new(60.0,80.0)
 }
@@ -32,6 +58,58 @@ pub fn GET_LOGO_POSITION() -> Vec2{
 	Vec2::new(16.0,30.0)
 }
 
+pub fn GET_MENU_POSITION() -> Vec2{
+	Vec2::new(20.0,68.0)
+}
+
+pub fn GET_LEVEL_TITLE_POSITION() -> Vec2{
+	Vec2::new(4.0,2.0)
+}
+
+pub fn GET_LEVEL_GRID_POSITION() -> Vec2{
+	Vec2::new(6.0,14.0)
+}
+
+pub fn GET_LEVEL_INFO_POSITION() -> Vec2{
+	Vec2::new(4.0,84.0)
+}
+
+pub fn GET_LEVEL_STARS_POSITION() -> Vec2{
+	Vec2::new(66.0,86.0)
+}
+
+pub fn GET_STATS_POSITION() -> Vec2{
+	Vec2::new(4.0,16.0)
+}
+
+pub fn GET_TOAST_POSITION() -> Vec2{
+	Vec2::new(0.0,0.0)
+}
+
+pub fn GET_EDITOR_TOOL_POSITION() -> Vec2{
+	Vec2::new(4.0,2.0)
+}
+
+pub fn GET_EDITOR_MOVES_POSITION() -> Vec2{
+	Vec2::new(50.0,2.0)
+}
+
+pub fn GET_MENU_MESSAGE_POSITION() -> Vec2{
+	Vec2::new(4.0,2.0)
+}
+
+pub fn GET_CLOCK_POSITION() -> Vec2{
+	Vec2::new(72.0,2.0)
+}
+
+pub fn GET_RESULTS_POSITION() -> Vec2{
+	Vec2::new(4.0,2.0)
+}
+
+pub fn GET_SPLITS_POSITION() -> Vec2{
+	Vec2::new(4.0,32.0)
+}
+
 pub fn GET_THX_POSITION() -> Vec2{
 	Vec2::new(16.0,30.0)
 }
@@ -40,3 +118,11 @@ pub fn GET_FUTURE_COLOR() -> Color{
 	Color::rgb8(60,67,101)
 }
 
+
+pub fn GET_VALUE_TINT(band: u8) -> Color{
+	match band{
+		0 => Color::rgb8(255,255,255),
+		1 => Color::rgb8(255,176,120),
+		_ => Color::rgb8(140,200,255),
+	}
+}
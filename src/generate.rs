@@ -0,0 +1,63 @@
+use std::convert::TryFrom;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::managers::levelmanager::level_files;
+use crate::models::level::parse_level;
+use crate::models::generator::{Difficulty, Generator};
+
+const USAGE: &str = "usage: --generate <easy|medium|hard> <seed> [count] [directory]";
+
+// writes new levels as ron files, returns the exit code
+pub fn run(args: &[String]) -> i32{
+	let difficulty = args.first().and_then(|d|Difficulty::parse(d));
+	let seed = args.get(1).and_then(|s|s.parse::<u64>().ok());
+	let (difficulty, seed) = match (difficulty, seed){
+		(Some(difficulty), Some(seed)) => (difficulty, seed),
+		_ => {
+			println!("{}", USAGE);
+			return 1;
+		}
+	};
+	let count = args.get(2).and_then(|c|c.parse::<usize>().ok()).unwrap_or(1);
+	let directory = PathBuf::from(args.get(3).map(|d|d.as_str()).unwrap_or("."));
+	if let Err(error) = fs::create_dir_all(&directory){
+		println!("can't create {}: {}", directory.display(), error);
+		return 1;
+	}
+
+	let mut generator = Generator::new(seed);
+	// numbers continue after the highest one in use, there can be gaps below it
+	let highest = level_files().iter()
+		.filter_map(|(_, content)|parse_level(content).ok())
+		.map(|level|usize::from(level.number))
+		.max()
+		.unwrap_or(0);
+	for i in 0..count{
+		let number = match u8::try_from(highest + 1 + i){
+			Ok(number) => number,
+			Err(_) => {
+				println!("no level numbers left after {}", highest + i);
+				return 1;
+			}
+		};
+		let path = directory.join(format!("level_{}.ron", number));
+		if path.exists(){
+			println!("{} already exists, not overwriting it", path.display());
+			return 1;
+		}
+		let level = match generator.generate(difficulty, number){
+			Some(level) => level,
+			None => {
+				println!("no {:?} level found for seed {}", difficulty, seed);
+				return 1;
+			}
+		};
+		if let Err(error) = fs::write(&path, level.to_ron()){
+			println!("can't write {}: {}", path.display(), error);
+			return 1;
+		}
+		println!("{}: \"{}\" {} moves", path.display(), level.name, level.moves);
+	}
+	0
+}
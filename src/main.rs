@@ -8,21 +8,47 @@ mod models;
 mod utils;
 mod assets;
 mod button;
+mod menu;
+mod pager;
+mod toast;
 mod tile;
 mod constants;
 mod managers;
+mod validate;
+mod generate;
 
 use tetra::ContextBuilder;
 use std::rc::Rc;
 use std::cell::{RefCell};
+use std::env;
+use std::path::Path;
 
 use crate::scenes::manager::SceneManager;
 use crate::scenes::title::TitleScene;
+use crate::scenes::replay::ReplayScene;
+use crate::models::replay::load_replay;
 use crate::assets::Assets;
 use crate::models::config::{load_config};
 
 fn main() -> tetra::Result {
 	color_backtrace::install();
+	let args = env::args().collect::<Vec<_>>();
+	if args.iter().any(|arg| arg == "--validate"){
+		std::process::exit(validate::run());
+	}
+	if let Some(i) = args.iter().position(|arg| arg == "--generate"){
+		std::process::exit(generate::run(&args[i+1..]));
+	}
+	let replay = match args.iter().position(|arg| arg == "--replay").and_then(|i|args.get(i+1)){
+		Some(path) => match load_replay(Path::new(path)){
+			Ok(replay) => Some(replay),
+			Err(error) => {
+				println!("can't load replay {}: {}", path, error);
+				std::process::exit(1);
+			}
+		},
+		None => None,
+	};
 	simple_logger::init().unwrap();
 	let config = Rc::new(load_config(include_str!("../assets/config/config.ron")));
 	let version = config.version();
@@ -38,6 +64,10 @@ fn main() -> tetra::Result {
 	.build()?
 		.run_with(|ctx| {
 			let assets = Rc::new(RefCell::new(Assets::init(ctx)?));
+			if let Some(replay) = replay{
+				let scene = ReplayScene::new(ctx,config,assets,replay)?;
+				return Ok(SceneManager::new(Box::new(scene)));
+			}
 			let scene = TitleScene::new(ctx,config,assets)?;
 			Ok(SceneManager::new(Box::new(scene)))
 		})
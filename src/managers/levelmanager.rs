@@ -1,24 +1,95 @@
-use crate::models::level::{Level,load_level};
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::models::level::{Level,parse_level};
+use crate::models::pack::{Pack, EDITOR_PACK, load_pack, is_pack};
+use crate::utils::storage;
 
 pub struct Levelmanager{
+	current_pack: usize,
 	current_level: usize,
-	levels: Vec<Level>,
+	packs: Rc<Vec<Pack>>,
+	hints_used: Vec<u32>,
 }
 
+#[allow(dead_code)]
 impl Levelmanager{
-	pub fn new(starting_level: usize) -> tetra::Result<Levelmanager>{
-		Ok(Levelmanager{
-			current_level: starting_level,
-			levels: load_levels(),
-		})
+	// the packs are read from disk once and shared by every scene
+	pub fn new(packs: Rc<Vec<Pack>>) -> Levelmanager{
+		Levelmanager{
+			current_pack: 0,
+			current_level: 1,
+			hints_used: vec![0; packs[0].levels.len()],
+			packs,
+		}
+	}
+
+	pub fn from_pack(pack: Pack) -> Levelmanager{
+		Levelmanager::new(Rc::new(vec![pack]))
+	}
+
+	fn levels(&self) -> &Vec<Level>{
+		&self.packs[self.current_pack].levels
 	}
 
 	pub fn get_current_level(&self)-> &Level{
-		&self.levels[self.current_level-1]
+		&self.levels()[self.current_level-1]
+	}
+
+	pub fn get_levels(&self) -> &[Level]{
+		self.levels()
+	}
+
+	pub fn get_pack(&self) -> &Pack{
+		&self.packs[self.current_pack]
+	}
+
+	pub fn get_packs(&self) -> &[Pack]{
+		&self.packs
+	}
+
+	pub fn get_shared_packs(&self) -> Rc<Vec<Pack>>{
+		Rc::clone(&self.packs)
+	}
+
+	// starts over at the first level of the pack
+	pub fn select_pack(&mut self, name: &str) -> bool{
+		match self.packs.iter().position(|p|p.name() == name){
+			Some(index) => {
+				self.current_pack = index;
+				self.current_level = 1;
+				self.hints_used = vec![0; self.levels().len()];
+				true
+			}
+			None => false,
+		}
+	}
+
+	pub fn select_level(&mut self, number: u8) -> bool{
+		match self.levels().iter().position(|l|l.number == number){
+			Some(index) => {
+				self.current_level = index + 1;
+				true
+			}
+			None => false,
+		}
+	}
+
+	pub fn add_hint(&mut self){
+		self.hints_used[self.current_level-1] +=1;
+	}
+
+	pub fn get_hints_used(&self) -> u32{
+		self.hints_used[self.current_level-1]
+	}
+
+	pub fn get_next_level_number(&self) -> Option<u8>{
+		self.levels().get(self.current_level).map(|l|l.number)
 	}
 
 	pub fn advance_next_level(&mut self)-> bool{
-		if self.current_level < self.levels.len(){
+		if self.current_level < self.levels().len(){
 			self.current_level +=1;
 			return true;
 		}
@@ -26,20 +97,164 @@ impl Levelmanager{
 	}
 }
 
-fn load_levels() ->Vec<Level>{
-	vec![
-		load_level(include_str!("../../assets/levels/level_1.ron")),
-		load_level(include_str!("../../assets/levels/level_2.ron")),
-		load_level(include_str!("../../assets/levels/level_3.ron")),
-		load_level(include_str!("../../assets/levels/level_4.ron")),
-		load_level(include_str!("../../assets/levels/level_5.ron")),
-		load_level(include_str!("../../assets/levels/level_6.ron")),
-		load_level(include_str!("../../assets/levels/level_7.ron")),
-		load_level(include_str!("../../assets/levels/level_8.ron")),
-		load_level(include_str!("../../assets/levels/level_9.ron")),
-		load_level(include_str!("../../assets/levels/level_10.ron")),
-		load_level(include_str!("../../assets/levels/level_11.ron")),
-		load_level(include_str!("../../assets/levels/level_12.ron")),
-		load_level(include_str!("../../assets/levels/level_13.ron")),
-	]
-}
\ No newline at end of file
+pub const LEVEL_FILES: [(&str, &str); 13] = [
+	("level_1.ron", include_str!("../../assets/levels/level_1.ron")),
+	("level_2.ron", include_str!("../../assets/levels/level_2.ron")),
+	("level_3.ron", include_str!("../../assets/levels/level_3.ron")),
+	("level_4.ron", include_str!("../../assets/levels/level_4.ron")),
+	("level_5.ron", include_str!("../../assets/levels/level_5.ron")),
+	("level_6.ron", include_str!("../../assets/levels/level_6.ron")),
+	("level_7.ron", include_str!("../../assets/levels/level_7.ron")),
+	("level_8.ron", include_str!("../../assets/levels/level_8.ron")),
+	("level_9.ron", include_str!("../../assets/levels/level_9.ron")),
+	("level_10.ron", include_str!("../../assets/levels/level_10.ron")),
+	("level_11.ron", include_str!("../../assets/levels/level_11.ron")),
+	("level_12.ron", include_str!("../../assets/levels/level_12.ron")),
+	("level_13.ron", include_str!("../../assets/levels/level_13.ron")),
+];
+
+// ron files from the level directories, or the embedded jam levels if there are none
+pub fn level_files() -> Vec<(String, String)>{
+	let mut files = vec![];
+	for directory in storage::level_dirs(){
+		files.extend(ron_files(&directory));
+	}
+	if files.is_empty(){
+		files = embedded_level_files();
+	}
+	files
+}
+
+// name and content of every ron file in the directory, sorted by name
+pub fn ron_files(directory: &Path) -> Vec<(String, String)>{
+	let mut files = vec![];
+	let entries = match fs::read_dir(directory){
+		Ok(entries) => entries,
+		Err(_) => return files,
+	};
+	let mut paths = entries.filter_map(|entry|entry.ok()).map(|entry|entry.path())
+		.filter(|path|path.extension().is_some_and(|extension|extension == "ron"))
+		.collect::<Vec<_>>();
+	paths.sort();
+	for path in paths{
+		match fs::read_to_string(&path){
+			Ok(content) => files.push((path.display().to_string(), content)),
+			Err(error) => log::warn!("can't read {}: {}", path.display(), error),
+		}
+	}
+	files
+}
+
+fn embedded_level_files() -> Vec<(String, String)>{
+	LEVEL_FILES.iter().map(|(file, content)|(file.to_string(), content.to_string())).collect()
+}
+
+// the jam levels for the tests of the models, a broken one fails the test
+#[cfg(test)]
+pub fn embedded_levels() -> Vec<Level>{
+	LEVEL_FILES.iter().map(|(file, content)|parse_level(content).unwrap_or_else(|e|panic!("{}: {}", file, e))).collect()
+}
+
+// the jam levels first, then every pack found in the pack directories and the editor levels last
+pub fn load_packs() -> Vec<Pack>{
+	let mut packs = vec![Pack::jam(load_levels(level_files()))];
+	for directory in storage::pack_dirs(){
+		let entries = match fs::read_dir(&directory){
+			Ok(entries) => entries,
+			Err(_) => continue,
+		};
+		let mut paths = entries.filter_map(|entry|entry.ok()).map(|entry|entry.path())
+			.filter(|path|is_pack(path))
+			.collect::<Vec<_>>();
+		paths.sort();
+		for path in paths{
+			match load_pack(&path){
+				Ok(pack) if pack.name() == packs[0].name() => log::warn!("skipping {}: the name is taken by the built-in levels", path.display()),
+				Ok(pack) => {
+					log::info!("pack \"{}\" {} by {} with {} levels", pack.name(), pack.manifest.version, pack.manifest.author, pack.levels.len());
+					packs.retain(|p|p.name() != pack.name());
+					packs.push(pack);
+				}
+				Err(error) => log::warn!("skipping pack {}: {}", path.display(), error),
+			}
+		}
+	}
+	let editor_levels = storage::editor_dir().map_or(vec![], |directory|parse_levels(ron_files(&directory)));
+	if !editor_levels.is_empty(){
+		if packs.iter().any(|p|p.name() == EDITOR_PACK){
+			log::warn!("levels from the editor are hidden by the pack \"{}\"", EDITOR_PACK);
+		}else{
+			packs.push(Pack::custom(EDITOR_PACK, editor_levels));
+		}
+	}
+	packs
+}
+
+fn load_levels(files: Vec<(String, String)>) ->Vec<Level>{
+	let levels = parse_levels(files);
+	if levels.is_empty(){
+		log::warn!("no playable level found, using the embedded levels");
+		return parse_levels(embedded_level_files());
+	}
+	levels
+}
+
+// broken files are reported and skipped, a later file replaces a level with the same number
+fn parse_levels(files: Vec<(String, String)>) -> Vec<Level>{
+	let mut levels: Vec<Level> = vec![];
+	for (file, content) in files{
+		let level = match parse_level(&content){
+			Ok(level) => level,
+			Err(error) => {
+				log::warn!("skipping {}: {}", file, error);
+				continue;
+			}
+		};
+		let errors = level.check();
+		if !errors.is_empty(){
+			log::warn!("skipping {}: {}", file, errors.join(", "));
+			continue;
+		}
+		levels.retain(|l|l.number != level.number);
+		levels.push(level);
+	}
+	levels.sort_by_key(|l|l.number);
+	levels
+}
+
+#[cfg(test)]
+mod tests{
+	use super::*;
+	use crate::models::pack::DEFAULT_PACK;
+
+	#[test]
+	fn broken_level_files_fall_back_to_the_jam_levels(){
+		let files = vec![
+			("broken.ron".to_string(), "(".to_string()),
+			("empty.ron".to_string(), String::new()),
+		];
+		let levels = load_levels(files);
+		assert_eq!(levels.len(), LEVEL_FILES.len());
+		assert_eq!(levels.iter().map(|l|l.number).collect::<Vec<_>>(), (1..=LEVEL_FILES.len() as u8).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn a_later_file_replaces_the_level(){
+		let mut replaced = parse_level(LEVEL_FILES[1].1).unwrap();
+		replaced.number = 1;
+		let files = vec![
+			("first.ron".to_string(), LEVEL_FILES[0].1.to_string()),
+			("second.ron".to_string(), replaced.to_ron()),
+		];
+		let levels = load_levels(files);
+		assert_eq!(levels.len(), 1);
+		assert_eq!(levels[0].values, replaced.values);
+	}
+
+	#[test]
+	fn the_jam_pack_comes_first(){
+		let packs = load_packs();
+		assert_eq!(packs[0].name(), DEFAULT_PACK);
+		assert!(!packs[0].levels.is_empty());
+	}
+}
@@ -1,113 +1,267 @@
 use crate::tile::Tile;
 use std::cell::RefCell;
 use std::rc::Rc;
-use crate::assets::Assets;
+use crate::assets::{Assets, TextureName, AnimationName};
 use crate::models::level::Level;
-use tetra::graphics::{Rectangle, Vec2, DrawParams, Drawable};
-use crate::constants::{TILE_SIZE, LEFT_BORDER_SPACING, TOP_BORDER_SPACING, TILE_GAP};
-use tetra::{Context, graphics};
+use crate::models::board::{Board, Outcome, FutureTrace};
+use crate::tile::TileState;
+use tetra::graphics::{Rectangle, Vec2, DrawParams, Drawable, Color};
+use crate::constants::{TILE_SIZE, LEFT_BORDER_SPACING, TOP_BORDER_SPACING, TILE_GAP, BOARD_WIDTH, BOARD_HEIGHT};
+use tetra::{Context, graphics, input};
+use tetra::input::Key;
 use crate::utils::particle::Particle;
 use rand::prelude::ThreadRng;
 use std::collections::HashSet;
 use rand::Rng;
 use crate::utils::timer::Timer;
+use crate::utils::history::History;
 
 pub struct Tilemanager{
+	board: Board,
+	history: History<Board>,
 	tiles: Vec<Tile>,
 	particles: Vec<Particle>,
 	randomizer: ThreadRng,
 	assets: Rc<RefCell<Assets>>,
 	ready_timer: Timer,
+	scale: f32,
+	playback: Option<Playback>,
+	highlighted: HashSet<usize>,
+	step_length: u32,
+	input_enabled: bool,
 }
 
+// the future replayed step by step, every step lasts step_length frames
+struct Playback{
+	trace: FutureTrace,
+	steps: Vec<Step>,
+	current: usize,
+	frames: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step{
+	Marks,
+	Highlight(usize),
+	Dissolve(usize),
+}
+
+const FAST_FORWARD: u32 = 4;
+
 impl Tilemanager{
-	pub fn new(assets: Rc<RefCell<Assets>>) -> tetra::Result<Tilemanager>{
+	pub fn new(assets: Rc<RefCell<Assets>>, step_length: u32) -> tetra::Result<Tilemanager>{
 		let randomizer = rand::thread_rng();
 		Ok(Tilemanager{
+			board: Board::default(),
+			history: History::new(),
 			tiles: vec![],
 			assets,
 			particles: vec![],
 			randomizer,
 			ready_timer: Timer::new(1),
-		}.init()?)
+			scale: 1.0,
+			playback: None,
+			highlighted: HashSet::new(),
+			step_length,
+			input_enabled: true,
+		})
 	}
 
-	pub fn init(mut self)-> tetra::Result<Self>{
-		let touch_area = Rectangle::new(0.0,0.0,16.0,16.0);
-		let positions = get_positions();
-		for i in 0..9{
-			self.tiles.push(Tile::new(Rc::clone(&self.assets), positions[i], touch_area, 0)?)
+	pub fn init_level(&mut self,level: &Level) -> tetra::Result{
+		self.board = Board::from_level(level);
+		self.history.clear();
+		self.playback = None;
+		self.highlighted.clear();
+		let (positions, scale) = get_positions(self.board.width, self.board.height);
+		let size = TILE_SIZE as f32 * scale;
+		let touch_area = Rectangle::new(0.0,0.0,size,size);
+		self.scale = scale;
+		self.tiles.clear();
+		for position in positions{
+			self.tiles.push(Tile::new(Rc::clone(&self.assets), position, touch_area, 0)?.set_scale(scale));
 		}
-		Ok(self)
+		self.sync_tiles();
+		Ok(())
 	}
 
-	pub fn init_level(&mut self,level: &Level){
+	fn sync_tiles(&mut self){
 		for (i,b) in self.tiles.iter_mut().enumerate(){
-			b.number(level.values[i]);
-			b.set_enabled(level.states[i]);
-			b.mark(level.flagged[i]);
-		}
-	}
-
-	pub fn go_future(&mut self) -> u8{
-		self.ready_timer.restart();
-		for tile in self.tiles.iter_mut(){
-			if tile.is_marked(){
-				tile.enable();
-				let particle = Particle::new(tile.position,Vec2::new(0.0,-1.0))
-					.set_aging(self.randomizer.gen_range(0.002,0.004))
-					.set_texture_name(tile.get_texture_name());
-				self.particles.push(particle);
-				tile.go_future();
+			b.number(self.board.values[i]);
+			b.set_wall(self.board.is_wall(i));
+			b.set_enabled(self.board.enabled[i]);
+			b.mark(self.board.marks[i]);
+		}
+	}
+
+	pub fn get_board(&self) -> &Board{
+		&self.board
+	}
+
+	pub fn actions(&self) -> u8{
+		self.board.actions
+	}
+
+	pub fn clear_actions(&mut self){
+		self.board.actions = 0;
+	}
+
+	pub fn undo(&mut self) -> bool{
+		match self.history.undo(self.board.clone()){
+			Some(board) => {
+				self.board = board;
+				self.sync_tiles();
+				true
+			}
+			None => false,
+		}
+	}
+
+	pub fn redo(&mut self) -> bool{
+		match self.history.redo(self.board.clone()){
+			Some(board) => {
+				self.board = board;
+				self.sync_tiles();
+				true
+			}
+			None => false,
+		}
+	}
+
+	// replays drive the tiles themselves
+	pub fn set_input_enabled(&mut self, enabled: bool){
+		self.input_enabled = enabled;
+	}
+
+	// same as clicking the tile with the left (plus) or right (minus) button
+	pub fn click(&mut self, index: usize, state: TileState) -> bool{
+		let before = self.board.clone();
+		if self.board.toggle_mark(index, state){
+			self.history.record(before);
+			self.tiles[index].mark(self.board.marks[index]);
+			return true;
+		}
+		false
+	}
+
+	pub fn can_undo(&self) -> bool{
+		self.history.can_undo()
+	}
+
+	pub fn can_redo(&self) -> bool{
+		self.history.can_redo()
+	}
+
+	// computes the whole future at once and starts playing it back step by step
+	pub fn go_future(&mut self) -> Outcome{
+		self.history.clear();
+		let trace = self.board.simulate_future();
+		let outcome = trace.outcome();
+		let mut steps = vec![Step::Marks];
+		for (r, round) in trace.rounds.iter().enumerate(){
+			if !round.tiles.is_empty(){
+				steps.push(Step::Highlight(r));
+				steps.push(Step::Dissolve(r));
 			}
 		}
+		self.playback = Some(Playback{
+			trace,
+			steps,
+			current: 0,
+			frames: 0,
+		});
+		self.play_step(Step::Marks);
+		outcome
+	}
 
-		let mut sum: u8 = self.tiles.iter().map(|t|t.number).sum();
-		if sum != 0{
-			for r in 0..4{
-				let numbers = self.tiles.iter().map(|t|t.number).collect::<Vec<_>>();
-				let tile_index = self.after_work(numbers);
-				self.change_tile_effect(tile_index.clone(), r);
-				for n in tile_index{
-					let num = self.tiles[n].number-1;
-					self.tiles[n].number(num);
+	pub fn is_playing(&self) -> bool{
+		self.playback.is_some()
+	}
+
+	// runs all remaining steps in this frame
+	pub fn skip(&mut self){
+		while self.is_playing(){
+			self.next_step();
+		}
+	}
+
+	fn next_step(&mut self){
+		let step = match self.playback.as_mut(){
+			Some(playback) => {
+				playback.current +=1;
+				playback.frames = 0;
+				playback.steps.get(playback.current).cloned()
+			}
+			None => return,
+		};
+		match step{
+			Some(step) => self.play_step(step),
+			None => {
+				self.playback = None;
+				self.highlighted.clear();
+				self.sync_tiles();
+				self.ready_timer.restart();
+			}
+		}
+	}
+
+	fn play_step(&mut self, step: Step){
+		let playback = match self.playback.take(){
+			Some(playback) => playback,
+			None => return,
+		};
+		let trace = &playback.trace;
+		match step{
+			Step::Marks => {
+				for &n in trace.marked.iter(){
+					let tile = &mut self.tiles[n];
+					tile.enable();
+					let particle = Particle::new(tile.position,Vec2::new(0.0,-1.0))
+						.set_aging(self.randomizer.gen_range(0.002,0.004))
+						.set_texture_name(tile.get_texture_name())
+						.set_color(tile.get_tint());
+					self.particles.push(particle);
+					tile.mark(TileState::Normal);
 				}
+				let values = trace.rounds.first().map_or(&self.board.values, |round|&round.values).clone();
+				self.show_values(&values);
+			}
+			Step::Highlight(r) => {
+				self.highlighted = trace.rounds[r].tiles.clone();
+			}
+			Step::Dissolve(r) => {
+				self.highlighted.clear();
+				let round = &trace.rounds[r];
+				self.change_tile_effect(round.tiles.clone(), round.diagonal.clone(), r);
+				let values = trace.rounds.get(r+1).map_or(&self.board.values, |round|&round.values).clone();
+				self.show_values(&values);
 			}
-			sum = self.tiles.iter().map(|t|t.number).sum();
 		}
-		sum
+		self.playback = Some(playback);
 	}
 
-	fn change_tile_effect(&mut self, tile_index: HashSet<usize>, round: usize){
+	fn show_values(&mut self, values: &[u8]){
+		for (tile, &value) in self.tiles.iter_mut().zip(values.iter()){
+			tile.number(value);
+		}
+	}
+
+	fn change_tile_effect(&mut self, tile_index: HashSet<usize>, diagonal: HashSet<usize>, round: usize){
 		let directions = vec![Vec2::new(1.0,0.0),Vec2::new(0.0,1.0),Vec2::new(-1.0,0.0),Vec2::new(0.0,-1.0),Vec2::new(1.0,0.0)];
 		for n in tile_index{
 			self.tiles[n].enable();
-			let particle = Particle::new(self.tiles[n].position,directions[round])
+			let direction = directions[round % directions.len()];
+			// turned by 45 degrees for tiles on a diagonal line
+			let direction = if diagonal.contains(&n) { Vec2::new(direction.x - direction.y, direction.x + direction.y) } else { direction };
+			let particle = Particle::new(self.tiles[n].position,direction)
 				.set_aging(self.randomizer.gen_range(0.002,0.004))
-				.set_texture_name(self.tiles[n].get_texture_name());
+				.set_texture_name(self.tiles[n].get_texture_name())
+				.set_color(self.tiles[n].get_tint());
 			self.particles.push(particle);
 		}
 	}
 
-	fn after_work(&mut self, numbers: Vec<u8>) -> HashSet<usize>{
-		let mut tile_index = HashSet::new();
-		for i in 0..3{
-			if numbers[i*3] == numbers[i*3+1] && numbers[i*3] == numbers[i*3+2] && numbers[i*3] != 0{
-				tile_index.insert(i*3);
-				tile_index.insert(i*3+1);
-				tile_index.insert(i*3+2);
-			}
-			if numbers[i] == numbers[i+3] && numbers[i] == numbers[i+6] && numbers[i] != 0{
-				tile_index.insert(i);
-				tile_index.insert(i+3);
-				tile_index.insert(i+6);
-			}
-		}
-		tile_index
-	}
-
 	pub fn is_ready(&self) -> bool{
-		self.ready_timer.finished
+		!self.is_playing() && self.ready_timer.finished
 	}
 
 	pub fn draw(&self, ctx: &mut Context){
@@ -115,28 +269,56 @@ impl Tilemanager{
 			b.draw(ctx, DrawParams::default());
 		}
 
+		for &n in self.highlighted.iter(){
+			graphics::draw(ctx, self.assets.borrow().get_texture(&TextureName::Highlight), DrawParams::new()
+				.position(self.tiles[n].position)
+				.scale(Vec2::new(self.scale, self.scale))
+			);
+		}
+
 		for p in self.particles.iter().rev() {
 			graphics::draw(ctx, self.assets.borrow().get_texture(&p.get_texture_name()), DrawParams::new()
 				.position(p.get_position())
+				.scale(Vec2::new(self.scale, self.scale))
 				.color(p.get_color())
 			);
 		}
 	}
 
-	pub fn update(&mut self, ctx: &mut Context, actions: &mut u8){
+	pub fn draw_hint(&self, ctx: &mut Context, index: usize, state: TileState){
+		let params = DrawParams::new()
+			.position(self.tiles[index].position)
+			.scale(Vec2::new(self.scale, self.scale));
+		let animation_name = if state == TileState::Plus { AnimationName::Plus } else { AnimationName::Minus };
+		graphics::draw(ctx, self.assets.borrow().get_animation(&animation_name), params.clone().color(Color::rgba(1.0,1.0,1.0,0.5)));
+		graphics::draw(ctx, self.assets.borrow().get_texture(&TextureName::Highlight), params);
+	}
+
+	// returns the tile whose mark changed and its new mark
+	pub fn update(&mut self, ctx: &mut Context) -> Option<(usize, TileState)>{
 		// timer update
 		self.ready_timer.update();
 
-		for b in self.tiles.iter_mut(){
-			if b.is_marked(){
-				b.update(ctx);
-				if !b.is_marked(){
-					*actions +=1;
+		// future playback, hold F to fast forward
+		if let Some(playback) = self.playback.as_mut(){
+			playback.frames += if input::is_key_down(ctx, Key::F) { FAST_FORWARD } else { 1 };
+			if playback.frames >= self.step_length{
+				self.next_step();
+			}
+		}
+
+		// no marks while the future plays
+		let mut changed = None;
+		if self.playback.is_none() && self.input_enabled{
+			let mut clicked = None;
+			for (i,b) in self.tiles.iter_mut().enumerate(){
+				if let Some(state) = b.update(ctx){
+					clicked = Some((i, state));
 				}
-			}else if actions > &mut 0{
-				b.update(ctx);
-				if b.is_marked(){
-					*actions -=1;
+			}
+			if let Some((i, state)) = clicked{
+				if self.click(i, state){
+					changed = Some((i, self.board.marks[i]));
 				}
 			}
 		}
@@ -146,16 +328,23 @@ impl Tilemanager{
 		for p in self.particles.iter_mut(){
 			p.update();
 		}
+		changed
 	}
 }
 
-fn get_positions() -> Vec<Vec2>{
-	let jump = TILE_SIZE + TILE_GAP;
+// tiles shrink when the board doesn't fit between the moves symbols and the buttons
+pub fn get_positions(width: usize, height: usize) -> (Vec<Vec2>, f32){
+	let jump = (TILE_SIZE + TILE_GAP) as f32;
+	let scale = (BOARD_WIDTH as f32 / (width as f32 * jump))
+		.min(BOARD_HEIGHT as f32 / (height as f32 * jump))
+		.min(1.0);
+	let center = LEFT_BORDER_SPACING as f32 + 1.5 * jump;
+	let left = center - width as f32 * jump * scale / 2.0;
 	let mut positions = vec![];
-	for i in 0..3{
-		positions.push(Vec2::new((LEFT_BORDER_SPACING+i*jump) as f32,TOP_BORDER_SPACING as f32));
-		positions.push(Vec2::new((LEFT_BORDER_SPACING+i*jump) as f32,(TOP_BORDER_SPACING + jump) as f32));
-		positions.push(Vec2::new((LEFT_BORDER_SPACING+i*jump) as f32,(TOP_BORDER_SPACING + 2*jump) as f32));
+	for x in 0..width{
+		for y in 0..height{
+			positions.push(Vec2::new(left + x as f32 * jump * scale, TOP_BORDER_SPACING as f32 + y as f32 * jump * scale));
+		}
 	}
-	positions
-}
\ No newline at end of file
+	(positions, scale)
+}
@@ -0,0 +1,111 @@
+use tetra::graphics::{self, DrawParams, Vec2, Rectangle, Color};
+use tetra::graphics::text::{Text, Font};
+use tetra::input::{self, Key, MouseButton};
+use tetra::{Context, glm};
+
+const ENTRY_HEIGHT: f32 = 10.0;
+const ENTRY_WIDTH: f32 = 80.0;
+// only a few entries fit below the logo, the rest scroll
+const VISIBLE_ENTRIES: usize = 3;
+
+// a vertical list of text entries, chosen with the arrow keys or the mouse
+pub struct Menu<T>{
+	position: Vec2,
+	entries: Vec<(T, String, Text)>,
+	selected: usize,
+	first_visible: usize,
+}
+
+#[allow(dead_code)]
+impl<T: Copy + PartialEq> Menu<T>{
+	pub fn new(position: Vec2, entries: Vec<(T, String)>) -> Menu<T>{
+		let mut menu = Menu{
+			position,
+			entries: vec![],
+			selected: 0,
+			first_visible: 0,
+		};
+		menu.set_entries(entries);
+		menu
+	}
+
+	// keeps the selection on the same entry if it still exists
+	pub fn set_entries(&mut self, entries: Vec<(T, String)>){
+		let selected = self.get_selected();
+		self.entries = entries.into_iter().map(|(action, label)|{
+			let text = Text::new(label.as_str(), Font::default(), 8.0);
+			(action, label, text)
+		}).collect();
+		self.selected = selected
+			.and_then(|action|self.entries.iter().position(|(a, _, _)|*a == action))
+			.unwrap_or(0);
+		self.first_visible = 0;
+		self.scroll_to_selected();
+	}
+
+	pub fn has_entries(&self, entries: &[(T, String)]) -> bool{
+		self.entries.len() == entries.len() && self.entries.iter().zip(entries).all(|((a, l, _), (action, label))|a == action && l == label)
+	}
+
+	pub fn get_selected(&self) -> Option<T>{
+		self.entries.get(self.selected).map(|(action, _, _)|*action)
+	}
+
+	fn scroll_to_selected(&mut self){
+		if self.selected < self.first_visible{
+			self.first_visible = self.selected;
+		}else if self.selected >= self.first_visible + VISIBLE_ENTRIES{
+			self.first_visible = self.selected + 1 - VISIBLE_ENTRIES;
+		}
+	}
+
+	fn entry_area(&self, row: usize) -> Rectangle{
+		Rectangle::new(self.position.x, self.position.y + row as f32 * ENTRY_HEIGHT, ENTRY_WIDTH, ENTRY_HEIGHT)
+	}
+
+	fn entry_at(&self, position: Vec2) -> Option<usize>{
+		let visible = self.entries.len().min(VISIBLE_ENTRIES);
+		(0..visible).find(|&row|{
+			let area = self.entry_area(row);
+			position.x >= area.x && position.x < area.x + area.width && position.y >= area.y && position.y < area.y + area.height
+		}).map(|row|self.first_visible + row)
+	}
+
+	// returns the entry that got chosen this frame
+	pub fn update(&mut self, ctx: &mut Context) -> Option<T>{
+		if self.entries.is_empty(){
+			return None;
+		}
+		if input::is_key_pressed(ctx, Key::Up) || input::is_key_pressed(ctx, Key::W){
+			self.selected = if self.selected == 0 { self.entries.len() - 1 } else { self.selected - 1 };
+		}else if input::is_key_pressed(ctx, Key::Down) || input::is_key_pressed(ctx, Key::S){
+			self.selected = (self.selected + 1) % self.entries.len();
+		}
+
+		let mouse_position = glm::round(&input::get_mouse_position(ctx));
+		let hovered = self.entry_at(mouse_position);
+		if let Some(index) = hovered{
+			self.selected = index;
+		}
+		self.scroll_to_selected();
+
+		let clicked = hovered.is_some() && input::is_mouse_button_released(ctx, MouseButton::Left);
+		if clicked || input::is_key_released(ctx, Key::Return) || input::is_key_released(ctx, Key::Space){
+			self.get_selected()
+		}else{
+			None
+		}
+	}
+
+	pub fn draw(&self, ctx: &mut Context){
+		let visible = self.entries.iter().enumerate().skip(self.first_visible).take(VISIBLE_ENTRIES);
+		for (row, (index, (_, _, text))) in visible.enumerate(){
+			let color = if index == self.selected { Color::rgb(1.0, 1.0, 1.0) } else { Color::rgba(1.0, 1.0, 1.0, 0.5) };
+			let area = self.entry_area(row);
+			graphics::draw(ctx, text, DrawParams::new()
+				.position(Vec2::new(area.x, area.y))
+				.color(color)
+				);
+		}
+	}
+}
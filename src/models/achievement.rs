@@ -0,0 +1,152 @@
+use ron::de::from_str;
+use serde::Deserialize;
+
+use crate::models::save::SaveGame;
+use crate::tile::TileState;
+
+const ACHIEVEMENTS: &str = include_str!("../../assets/achievements.ron");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Achievement{
+	// what the save game remembers, names can change
+	pub id: String,
+	pub name: String,
+	pub description: String,
+	pub condition: Condition,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Condition{
+	// every mark the player placed was this one
+	OnlyMarks(TileState),
+	// marks left over on a win
+	MovesToSpare(u8),
+	// won without losing it before, on this level number or any
+	FirstTry(Option<u8>),
+	NoHints,
+	Stars(u8),
+	// levels completed in the current pack
+	LevelsCompleted(u32),
+	DailySolved(u32),
+	DailyStreak(u32),
+	EndlessCleared(u32),
+	// a whole time attack run below this many milliseconds
+	TimeAttack(u64),
+}
+
+// what happened in the game scene, the save game is already updated
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent{
+	Won(Win),
+	DailySolved,
+	EndlessOver(u32),
+	TimeAttackFinished(u64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Win{
+	// only levels of a pack have a number that means something
+	pub level: Option<u8>,
+	// marks the player placed, given ones don't count
+	pub marks: Vec<TileState>,
+	pub spare_moves: u8,
+	pub first_try: bool,
+	pub hints: u32,
+	pub stars: u8,
+}
+
+impl Condition{
+	fn is_met(self, event: &GameEvent, save: &SaveGame) -> bool{
+		match (self, event){
+			(Condition::OnlyMarks(mark), GameEvent::Won(win)) => !win.marks.is_empty() && win.marks.iter().all(|&m|m == mark),
+			(Condition::MovesToSpare(moves), GameEvent::Won(win)) => win.spare_moves >= moves,
+			(Condition::FirstTry(level), GameEvent::Won(win)) => win.first_try && level.is_none_or(|number|win.level == Some(number)),
+			(Condition::NoHints, GameEvent::Won(win)) => win.hints == 0,
+			(Condition::Stars(stars), GameEvent::Won(win)) => win.stars >= stars,
+			(Condition::LevelsCompleted(count), GameEvent::Won(win)) => win.level.is_some() && save.completed_levels() >= count,
+			(Condition::DailySolved(count), GameEvent::DailySolved) => save.daily.solved >= count,
+			(Condition::DailyStreak(days), GameEvent::DailySolved) => save.daily.streak >= days,
+			(Condition::EndlessCleared(count), GameEvent::EndlessOver(cleared)) => *cleared >= count,
+			(Condition::TimeAttack(limit), GameEvent::TimeAttackFinished(total)) => *total < limit,
+			_ => false,
+		}
+	}
+}
+
+// the list that comes with the game, a broken file only costs the achievements
+pub fn load_achievements() -> Vec<Achievement>{
+	match from_str(ACHIEVEMENTS){
+		Ok(achievements) => achievements,
+		Err(error) => {
+			log::warn!("can't read the achievements: {}", error);
+			vec![]
+		}
+	}
+}
+
+// unlocks everything the event earns and returns what is new
+pub fn unlock<'a>(achievements: &'a [Achievement], save: &mut SaveGame, event: &GameEvent) -> Vec<&'a Achievement>{
+	let earned = achievements.iter()
+		.filter(|a|!save.has_achievement(&a.id) && a.condition.is_met(event, save))
+		.collect::<Vec<_>>();
+	for achievement in earned.iter(){
+		save.unlock_achievement(&achievement.id);
+	}
+	earned
+}
+
+#[cfg(test)]
+mod tests{
+	use super::*;
+	use std::collections::HashSet;
+	use crate::models::pack::DEFAULT_PACK;
+
+	fn ids(unlocked: Vec<&Achievement>) -> HashSet<&str>{
+		unlocked.into_iter().map(|a|a.id.as_str()).collect()
+	}
+
+	#[test]
+	fn the_achievements_file_parses_with_unique_ids(){
+		let achievements: Vec<Achievement> = from_str(ACHIEVEMENTS).unwrap();
+		assert_eq!(achievements.len(), load_achievements().len());
+		assert_eq!(achievements.iter().map(|a|a.id.as_str()).collect::<HashSet<_>>().len(), achievements.len());
+	}
+
+	#[test]
+	fn a_win_unlocks_what_it_earns_once(){
+		let achievements = load_achievements();
+		let mut save = SaveGame::default();
+		save.pack = DEFAULT_PACK.to_string();
+		for number in 1..=13{
+			save.complete_level(number, 1, 3, Some(number + 1));
+		}
+		let win = GameEvent::Won(Win{
+			level: Some(13),
+			marks: vec![TileState::Minus],
+			spare_moves: 1,
+			first_try: true,
+			hints: 0,
+			stars: 3,
+		});
+		let unlocked = ids(unlock(&achievements, &mut save, &win));
+		let expected = ["first_win", "only_minus", "moves_to_spare", "three_stars", "no_hints", "all_levels"];
+		assert_eq!(unlocked, expected.iter().cloned().collect());
+		assert!(save.has_achievement("all_levels"));
+		assert!(unlock(&achievements, &mut save, &win).is_empty());
+	}
+
+	#[test]
+	fn daily_endless_and_time_attack_unlock_at_their_thresholds(){
+		let achievements = load_achievements();
+		let mut save = SaveGame::default();
+		for day in 0..7{
+			save.daily.solve(day);
+		}
+		let unlocked = ids(unlock(&achievements, &mut save, &GameEvent::DailySolved));
+		assert_eq!(unlocked, ["daily_1", "daily_streak_7"].iter().cloned().collect());
+		assert!(unlock(&achievements, &mut save, &GameEvent::EndlessOver(9)).is_empty());
+		assert_eq!(ids(unlock(&achievements, &mut save, &GameEvent::EndlessOver(10))), ["endless_10"].iter().cloned().collect());
+		assert!(unlock(&achievements, &mut save, &GameEvent::TimeAttackFinished(300_000)).is_empty());
+		assert_eq!(ids(unlock(&achievements, &mut save, &GameEvent::TimeAttackFinished(299_999))), ["time_attack_5"].iter().cloned().collect());
+	}
+}
@@ -0,0 +1,430 @@
+use std::collections::HashSet;
+use serde::{Serialize, Deserialize};
+
+use crate::models::level::{Level, MAX_SIZE};
+use crate::tile::TileState;
+
+pub const MAXNUMBER: u8 = 4;
+// above MAXNUMBER the tile sprites are reused with a tint
+pub const MAX_VALUE_LIMIT: u8 = 12;
+pub const ROUNDS: usize = 4;
+pub const LINE_LENGTH: usize = 3;
+// a round with a match lowers the sum by at least a line, so a valid level
+// runs out of matches before this. boards that skip the level check can hit it
+pub const MAX_ROUNDS: usize = MAX_SIZE * MAX_SIZE * MAX_VALUE_LIMIT as usize / LINE_LENGTH;
+
+// the puzzle rules without any rendering, usable without a tetra context
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Board{
+	pub width: usize,
+	pub height: usize,
+	pub diagonals: bool,
+	pub rounds: Rounds,
+	pub max_value: u8,
+	pub overflow: Overflow,
+	pub values: Vec<u8>,
+	pub kinds: Vec<TileKind>,
+	pub enabled: Vec<bool>,
+	pub marks: Vec<TileState>,
+	pub actions: u8,
+	pub remaining_moves: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct FutureTrace{
+	pub marked: Vec<usize>,
+	pub rounds: Vec<DissolveRound>,
+	// up to the number of tiles times MAX_VALUE_LIMIT, more than a u8 holds
+	pub remaining: u32,
+	// lines still matched after MAX_ROUNDS
+	pub capped: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+	Cleared,
+	Remaining(u32),
+	Capped,
+}
+
+impl FutureTrace{
+	pub fn outcome(&self) -> Outcome{
+		if self.capped{
+			Outcome::Capped
+		}else if self.remaining == 0{
+			Outcome::Cleared
+		}else{
+			Outcome::Remaining(self.remaining)
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TileKind {
+	Normal,
+	// never dissolves, can't be marked and splits the lines through it. in the
+	// rules that is what a tile at 0 does too, a wall only differs in that the
+	// editor and the level check never let it hold a value or a mark
+	Wall,
+}
+
+// what a mark does at the ends of the value range
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Overflow {
+	#[default]
+	Wrap,
+	Clamp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Rounds {
+	Fixed(u8),
+	UntilStable,
+}
+
+impl Default for Rounds {
+	fn default() -> Rounds {
+		Rounds::Fixed(ROUNDS as u8)
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct DissolveRound{
+	// values before the matched tiles get decremented
+	pub values: Vec<u8>,
+	pub tiles: HashSet<usize>,
+	// the part of tiles matched by a diagonal line
+	pub diagonal: HashSet<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Line{
+	pub tiles: Vec<usize>,
+	pub diagonal: bool,
+}
+
+#[allow(dead_code)]
+impl Board{
+	pub fn from_level(level: &Level) -> Board{
+		Board{
+			width: level.width,
+			height: level.height,
+			diagonals: level.diagonals,
+			rounds: level.rounds,
+			max_value: level.max_value,
+			overflow: level.overflow,
+			values: level.values.clone(),
+			kinds: level.kinds.clone(),
+			enabled: level.states.clone(),
+			marks: level.flagged.clone(),
+			actions: level.moves,
+			remaining_moves: level.remaining_moves,
+		}
+	}
+
+	pub fn len(&self) -> usize{
+		self.values.len()
+	}
+
+	// tiles are stored column by column, like the level files
+	pub fn index(&self, x: usize, y: usize) -> usize{
+		x * self.height + y
+	}
+
+	pub fn lines(&self) -> Vec<Line>{
+		lines(self.width, self.height, self.diagonals)
+	}
+
+	pub fn is_wall(&self, index: usize) -> bool{
+		self.kinds[index] == TileKind::Wall
+	}
+
+	pub fn is_clickable(&self, index: usize) -> bool{
+		!self.is_wall(index) && self.enabled[index] && self.values[index] > 0
+	}
+
+	pub fn is_marked(&self, index: usize) -> bool{
+		self.marks[index] != TileState::Normal
+	}
+
+	// same behaviour as clicking a tile: the same mark again removes it and
+	// gives the action back, switching between plus and minus is free
+	pub fn toggle_mark(&mut self, index: usize, state: TileState) -> bool{
+		if !self.is_clickable(index) || state == TileState::Normal{
+			return false;
+		}
+		if self.marks[index] == state{
+			self.marks[index] = TileState::Normal;
+			self.actions +=1;
+		}else if self.is_marked(index){
+			self.marks[index] = state;
+		}else if self.actions > 0{
+			self.marks[index] = state;
+			self.actions -=1;
+		}else{
+			return false;
+		}
+		true
+	}
+
+	pub fn can_go_future(&self) -> bool{
+		self.actions <= self.remaining_moves
+	}
+
+	pub fn sum(&self) -> u32{
+		self.values.iter().map(|&value|u32::from(value)).sum()
+	}
+
+	pub fn apply_marks(&mut self) -> Vec<usize>{
+		let mut marked = vec![];
+		for i in 0..self.len(){
+			if self.marks[i] == TileState::Normal || self.is_wall(i){
+				continue;
+			}
+			self.values[i] = marked_value(self.values[i], self.marks[i], self.max_value, self.overflow);
+			self.marks[i] = TileState::Normal;
+			self.enabled[i] = true;
+			marked.push(i);
+		}
+		marked
+	}
+
+	pub fn matching_lines(&self) -> Vec<Line>{
+		self.lines().into_iter().filter(|line|{
+			let first = self.values[line.tiles[0]];
+			// walls are 0 as well, the check only guards against a broken board
+			first != 0 && line.tiles.iter().all(|&n|self.values[n] == first && !self.is_wall(n))
+		}).collect()
+	}
+
+	pub fn matching_tiles(&self) -> HashSet<usize>{
+		self.matching_lines().into_iter().flat_map(|line|line.tiles).collect()
+	}
+
+	pub fn simulate_future(&mut self) -> FutureTrace{
+		let marked = self.apply_marks();
+		let mut rounds = vec![];
+		let mut capped = false;
+		if self.sum() != 0{
+			let (count, until_stable) = match self.rounds{
+				Rounds::Fixed(count) => (usize::from(count).min(MAX_ROUNDS), false),
+				Rounds::UntilStable => (MAX_ROUNDS, true),
+			};
+			for _ in 0..count{
+				let mut tiles = HashSet::new();
+				let mut diagonal = HashSet::new();
+				for line in self.matching_lines(){
+					if line.diagonal{
+						diagonal.extend(line.tiles.iter().cloned());
+					}
+					tiles.extend(line.tiles);
+				}
+				if until_stable && tiles.is_empty(){
+					break;
+				}
+				let values = self.values.clone();
+				for &n in tiles.iter(){
+					self.values[n] -=1;
+					self.enabled[n] = true;
+				}
+				rounds.push(DissolveRound{ values, tiles, diagonal });
+			}
+			capped = until_stable && rounds.len() == MAX_ROUNDS && !self.matching_lines().is_empty();
+		}
+		FutureTrace{
+			marked,
+			rounds,
+			remaining: self.sum(),
+			capped,
+		}
+	}
+}
+
+pub fn marked_value(value: u8, mark: TileState, max_value: u8, overflow: Overflow) -> u8{
+	match (mark, overflow){
+		(TileState::Plus, _) if value < max_value => value + 1,
+		(TileState::Plus, Overflow::Wrap) => 1,
+		(TileState::Plus, Overflow::Clamp) => max_value,
+		(TileState::Minus, _) if value > 1 => value - 1,
+		(TileState::Minus, Overflow::Wrap) => max_value,
+		(TileState::Minus, Overflow::Clamp) => 1,
+		(TileState::Normal, _) => value,
+	}
+}
+
+// every run of LINE_LENGTH tiles in a column, a row and optionally both diagonals
+pub fn lines(width: usize, height: usize, diagonals: bool) -> Vec<Line>{
+	let mut lines = vec![];
+	let fits_down = |y: usize| y + LINE_LENGTH <= height;
+	let fits_right = |x: usize| x + LINE_LENGTH <= width;
+	for x in 0..width{
+		for y in 0..height{
+			if fits_down(y){
+				lines.push(Line{ tiles: (0..LINE_LENGTH).map(|i|x * height + y + i).collect(), diagonal: false });
+			}
+			if fits_right(x){
+				lines.push(Line{ tiles: (0..LINE_LENGTH).map(|i|(x + i) * height + y).collect(), diagonal: false });
+			}
+			if diagonals && fits_right(x) && fits_down(y){
+				lines.push(Line{ tiles: (0..LINE_LENGTH).map(|i|(x + i) * height + y + i).collect(), diagonal: true });
+				lines.push(Line{ tiles: (0..LINE_LENGTH).map(|i|(x + i) * height + y + LINE_LENGTH - 1 - i).collect(), diagonal: true });
+			}
+		}
+	}
+	lines
+}
+
+#[cfg(test)]
+mod tests{
+	use super::*;
+	use crate::managers::levelmanager::embedded_levels;
+
+	#[test]
+	fn embedded_levels_need_marks(){
+		for level in embedded_levels(){
+			let trace = Board::from_level(&level).simulate_future();
+			assert!(!trace.capped, "level {} never settles", level.number);
+			assert_ne!(trace.remaining, 0, "level {} is won without a mark", level.number);
+			assert!(trace.rounds.len() <= ROUNDS, "level {} plays too many rounds", level.number);
+		}
+	}
+
+	#[test]
+	fn marks_are_applied_before_the_rounds(){
+		for level in embedded_levels(){
+			let mut board = Board::from_level(&level);
+			let clickable = (0..board.len()).find(|&i|board.is_clickable(i));
+			if let Some(i) = clickable.filter(|_|board.actions > 0){
+				let value = board.values[i];
+				assert!(board.toggle_mark(i, TileState::Plus));
+				let trace = board.simulate_future();
+				assert!(trace.marked.contains(&i));
+				let first = trace.rounds.first().map_or(board.values[i], |round|round.values[i]);
+				assert_eq!(first, marked_value(value, TileState::Plus, level.max_value, level.overflow), "level {}", level.number);
+			}
+		}
+	}
+
+	#[test]
+	fn a_full_row_dissolves(){
+		let mut level = Level::blank(1, 3, 3);
+		for x in 0..3{
+			level.values[x * 3] = 1;
+		}
+		let trace = Board::from_level(&level).simulate_future();
+		assert_eq!(trace.remaining, 0);
+		assert_eq!(trace.rounds[0].tiles.len(), 3);
+	}
+
+	#[test]
+	fn toggling_gives_the_action_back(){
+		let mut level = Level::blank(1, 3, 3);
+		level.values[0] = 2;
+		level.states[0] = true;
+		level.moves = 1;
+		let mut board = Board::from_level(&level);
+		assert!(board.toggle_mark(0, TileState::Minus));
+		assert_eq!(board.actions, 0);
+		assert!(board.toggle_mark(0, TileState::Plus));
+		assert_eq!(board.actions, 0);
+		assert!(board.toggle_mark(0, TileState::Plus));
+		assert_eq!(board.actions, 1);
+		assert!(!board.is_marked(0));
+	}
+
+	#[test]
+	fn walls_never_match_nor_take_marks(){
+		let mut level = Level::blank(1, 3, 3);
+		level.moves = 1;
+		for x in 0..3{
+			level.values[x * 3] = 1;
+			level.states[x * 3] = true;
+		}
+		// a broken board with a value on the wall, the rules still leave it alone
+		let mut board = Board::from_level(&level);
+		board.kinds[3] = TileKind::Wall;
+		assert!(board.is_wall(3));
+		assert!(board.matching_lines().is_empty());
+		assert!(!board.toggle_mark(3, TileState::Plus));
+		assert_eq!(board.actions, 1);
+		board.marks[3] = TileState::Minus;
+		let trace = board.simulate_future();
+		assert!(trace.marked.is_empty());
+		assert!(trace.rounds.iter().all(|round|!round.tiles.contains(&3)));
+		assert_eq!(board.values[3], 1);
+		assert_eq!(trace.remaining, 3);
+	}
+
+	#[test]
+	fn values_wrap_or_clamp(){
+		assert_eq!(marked_value(4, TileState::Plus, 4, Overflow::Wrap), 1);
+		assert_eq!(marked_value(4, TileState::Plus, 4, Overflow::Clamp), 4);
+		assert_eq!(marked_value(1, TileState::Minus, 4, Overflow::Wrap), 4);
+		assert_eq!(marked_value(1, TileState::Minus, 4, Overflow::Clamp), 1);
+	}
+
+	#[test]
+	fn lines_of_a_small_board(){
+		assert_eq!(lines(3, 3, false).len(), 6);
+		assert_eq!(lines(3, 3, true).len(), 8);
+		assert!(lines(2, 2, true).is_empty());
+	}
+
+	#[test]
+	fn until_stable_runs_past_the_fixed_rounds(){
+		let mut level = Level::blank(1, 3, 3);
+		level.max_value = 6;
+		for x in 0..3{
+			level.values[x * 3] = 6;
+		}
+		let trace = Board::from_level(&level).simulate_future();
+		assert_eq!((trace.rounds.len(), trace.remaining), (ROUNDS, 6));
+		level.rounds = Rounds::UntilStable;
+		let trace = Board::from_level(&level).simulate_future();
+		assert_eq!((trace.rounds.len(), trace.remaining, trace.capped), (6, 0, false));
+		assert_eq!(trace.outcome(), Outcome::Cleared);
+	}
+
+	#[test]
+	fn the_biggest_sum_fits_into_the_rounds(){
+		assert!(MAX_ROUNDS * LINE_LENGTH >= MAX_SIZE * MAX_SIZE * usize::from(MAX_VALUE_LIMIT));
+		let mut level = Level::blank(1, MAX_SIZE, MAX_SIZE);
+		level.max_value = MAX_VALUE_LIMIT;
+		level.rounds = Rounds::UntilStable;
+		level.values = vec![MAX_VALUE_LIMIT; MAX_SIZE * MAX_SIZE];
+		assert!(level.check().is_empty(), "{:?}", level.check());
+		let trace = Board::from_level(&level).simulate_future();
+		assert_eq!(trace.outcome(), Outcome::Cleared);
+	}
+
+	#[test]
+	fn a_board_beyond_the_limits_is_capped(){
+		let mut board = Board::from_level(&Level::blank(1, 3, 3));
+		board.rounds = Rounds::UntilStable;
+		for x in 0..3{
+			board.values[x * 3] = u8::MAX;
+		}
+		let trace = board.simulate_future();
+		assert!(trace.capped);
+		assert_eq!(trace.rounds.len(), MAX_ROUNDS);
+		assert_eq!(trace.outcome(), Outcome::Capped);
+	}
+
+	#[test]
+	fn a_big_sum_is_not_a_win(){
+		let mut level = Level::blank(1, 6, 6);
+		level.max_value = MAX_VALUE_LIMIT;
+		for x in 0..6{
+			for y in 0..6{
+				level.values[x * 6 + y] = if (x + y) % 2 == 0 { 6 } else { 8 };
+			}
+		}
+		for &i in [1, 3, 5, 6].iter(){
+			level.values[i] = 9;
+		}
+		assert!(level.check().is_empty(), "{:?}", level.check());
+		let trace = Board::from_level(&level).simulate_future();
+		assert_eq!(trace.remaining, 256);
+		assert_eq!(trace.outcome(), Outcome::Remaining(256));
+	}
+}
@@ -0,0 +1,204 @@
+use crate::models::board::{Overflow, Rounds, TileKind};
+use crate::models::level::Level;
+use crate::tile::TileState;
+
+// level codes are a version byte, the rules, the name and one byte per tile,
+// followed by a fletcher-16 checksum, all written in crockford base32
+const VERSION: u8 = 1;
+const PREFIX: &str = "WID";
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const MAX_NAME: usize = 24;
+
+const DIAGONALS: u8 = 1;
+const CLAMP: u8 = 2;
+const UNTIL_STABLE: u8 = 4;
+
+const VALUE_BITS: u8 = 0x0f;
+const ENABLED: u8 = 0x10;
+const WALL: u8 = 0x20;
+const PLUS: u8 = 0x40;
+const MINUS: u8 = 0x80;
+
+pub fn encode(level: &Level) -> String{
+	let mut flags = 0;
+	if level.diagonals{
+		flags |= DIAGONALS;
+	}
+	if level.overflow == Overflow::Clamp{
+		flags |= CLAMP;
+	}
+	let rounds = match level.rounds{
+		Rounds::Fixed(count) => count,
+		Rounds::UntilStable => {
+			flags |= UNTIL_STABLE;
+			0
+		}
+	};
+	let name = level.name.bytes().take(MAX_NAME).collect::<Vec<_>>();
+	let mut data = vec![VERSION, level.width as u8, level.height as u8, flags, rounds, level.max_value, level.moves, level.remaining_moves, name.len() as u8];
+	data.extend(name);
+	for i in 0..level.tiles(){
+		let mut tile = level.values[i] & VALUE_BITS;
+		if level.states[i]{
+			tile |= ENABLED;
+		}
+		if level.kinds[i] == TileKind::Wall{
+			tile |= WALL;
+		}
+		tile |= match level.flagged[i]{
+			TileState::Plus => PLUS,
+			TileState::Minus => MINUS,
+			TileState::Normal => 0,
+		};
+		data.push(tile);
+	}
+	let (a, b) = checksum(&data);
+	data.push(a);
+	data.push(b);
+	format!("{}{}", PREFIX, to_base32(&data))
+}
+
+// spaces, dashes and the case of the letters don't matter
+pub fn decode(code: &str) -> Result<Level, String>{
+	let code = code.trim().to_uppercase().replace(|c: char|c.is_whitespace() || c == '-', "");
+	if !code.starts_with(PREFIX){
+		return Err("not a level code".to_string());
+	}
+	let mut data = from_base32(&code[PREFIX.len()..])?;
+	if data.len() < 2{
+		return Err("code is too short".to_string());
+	}
+	let b = data.pop().unwrap();
+	let a = data.pop().unwrap();
+	if checksum(&data) != (a, b){
+		return Err("code is damaged".to_string());
+	}
+	let mut bytes = data.into_iter();
+	let mut next = || bytes.next().ok_or_else(||"code is too short".to_string());
+	if next()? != VERSION{
+		return Err("code is from another version".to_string());
+	}
+	let (width, height) = (usize::from(next()?), usize::from(next()?));
+	let flags = next()?;
+	let rounds = next()?;
+	let max_value = next()?;
+	let moves = next()?;
+	let remaining_moves = next()?;
+	let name_length = next()?;
+	let name = (0..name_length).map(|_|next()).collect::<Result<Vec<_>, _>>()?;
+	let mut level = Level::blank(1, width, height);
+	level.name = String::from_utf8_lossy(&name).into_owned();
+	level.diagonals = flags & DIAGONALS != 0;
+	level.overflow = if flags & CLAMP != 0 { Overflow::Clamp } else { Overflow::Wrap };
+	level.rounds = if flags & UNTIL_STABLE != 0 { Rounds::UntilStable } else { Rounds::Fixed(rounds) };
+	level.max_value = max_value;
+	level.moves = moves;
+	level.remaining_moves = remaining_moves;
+	for i in 0..level.tiles(){
+		let tile = next()?;
+		level.values[i] = tile & VALUE_BITS;
+		level.states[i] = tile & ENABLED != 0;
+		level.kinds[i] = if tile & WALL != 0 { TileKind::Wall } else { TileKind::Normal };
+		level.flagged[i] = match tile & (PLUS | MINUS){
+			PLUS => TileState::Plus,
+			MINUS => TileState::Minus,
+			_ => TileState::Normal,
+		};
+	}
+	if next().is_ok(){
+		return Err("code is too long".to_string());
+	}
+	match level.check().first(){
+		Some(error) => Err(error.clone()),
+		None => Ok(level),
+	}
+}
+
+fn checksum(data: &[u8]) -> (u8, u8){
+	let (mut a, mut b) = (0u16, 0u16);
+	for &byte in data{
+		a = (a + u16::from(byte)) % 255;
+		b = (b + a) % 255;
+	}
+	(a as u8, b as u8)
+}
+
+fn to_base32(data: &[u8]) -> String{
+	let mut code = String::new();
+	let (mut buffer, mut bits) = (0u32, 0);
+	for &byte in data{
+		buffer = (buffer << 8) | u32::from(byte);
+		bits += 8;
+		while bits >= 5{
+			bits -= 5;
+			code.push(ALPHABET[((buffer >> bits) & 31) as usize] as char);
+		}
+	}
+	if bits > 0{
+		code.push(ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+	}
+	code
+}
+
+fn from_base32(code: &str) -> Result<Vec<u8>, String>{
+	let mut data = vec![];
+	let (mut buffer, mut bits) = (0u32, 0);
+	for c in code.chars(){
+		// crockford reads the letters that look like digits as digits
+		let c = match c{
+			'O' => '0',
+			'I' | 'L' => '1',
+			c => c,
+		};
+		let value = ALPHABET.iter().position(|&a|a as char == c).ok_or_else(||format!("'{}' is not part of a level code", c))?;
+		buffer = (buffer << 5) | value as u32;
+		bits += 5;
+		if bits >= 8{
+			bits -= 8;
+			data.push((buffer >> bits) as u8);
+		}
+	}
+	Ok(data)
+}
+
+#[cfg(test)]
+mod tests{
+	use super::*;
+	use crate::managers::levelmanager::embedded_levels;
+
+	#[test]
+	fn embedded_levels_survive_a_round_trip(){
+		for level in embedded_levels(){
+			let decoded = decode(&encode(&level)).unwrap_or_else(|e|panic!("level {}: {}", level.number, e));
+			assert_eq!(decoded.name, level.name, "level {}", level.number);
+			assert_eq!((decoded.width, decoded.height), (level.width, level.height), "level {}", level.number);
+			assert_eq!((decoded.moves, decoded.remaining_moves, decoded.max_value), (level.moves, level.remaining_moves, level.max_value), "level {}", level.number);
+			assert_eq!((decoded.diagonals, decoded.overflow, decoded.rounds), (level.diagonals, level.overflow, level.rounds), "level {}", level.number);
+			assert_eq!(decoded.values, level.values, "level {}", level.number);
+			assert_eq!(decoded.states, level.states, "level {}", level.number);
+			assert_eq!(decoded.kinds, level.kinds, "level {}", level.number);
+			assert_eq!(decoded.flagged, level.flagged, "level {}", level.number);
+		}
+	}
+
+	#[test]
+	fn codes_are_read_loosely(){
+		let level = embedded_levels().remove(0);
+		let code = encode(&level);
+		let (head, tail) = code.split_at(8);
+		assert!(decode(&format!(" {}-{} ", head.to_lowercase(), tail)).is_ok());
+	}
+
+	#[test]
+	fn damaged_codes_are_rejected(){
+		let level = embedded_levels().remove(0);
+		let code = encode(&level);
+		for position in PREFIX.len()..code.len(){
+			let mut damaged = code.clone().into_bytes();
+			damaged[position] = if damaged[position] == b'2' { b'3' } else { b'2' };
+			assert!(decode(&String::from_utf8(damaged).unwrap()).is_err(), "changing character {} went unnoticed", position);
+		}
+		assert_eq!(decode("WID").unwrap_err(), "code is too short");
+		assert_eq!(decode("ABC123").unwrap_err(), "not a level code");
+	}
+}
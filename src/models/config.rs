@@ -25,6 +25,9 @@ pub struct Config {
 	pub quit_on_escape: bool,
 	// Game Music
 	pub master_volume: f32,
+	// frames every step of the future playback is shown
+	#[serde(default = "default_future_step_length")]
+	pub future_step_length: u32,
 }
 
 impl Config {
@@ -34,6 +37,10 @@ impl Config {
 	}
 }
 
+fn default_future_step_length() -> u32{
+	20
+}
+
 pub fn load_config(path: &str) -> Config{
 	match from_str(path){
 		Ok(config) => config,
@@ -0,0 +1,113 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Serialize, Deserialize};
+
+use crate::models::generator::{Difficulty, Generator};
+use crate::models::level::Level;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DailyProgress{
+	// days since 1970-01-01 in utc
+	pub last_solved: Option<u64>,
+	pub streak: u32,
+	pub best_streak: u32,
+	pub solved: u32,
+}
+
+#[allow(dead_code)]
+impl DailyProgress{
+	pub fn is_solved(&self, day: u64) -> bool{
+		self.last_solved == Some(day)
+	}
+
+	// a streak ends when a whole day is missed
+	pub fn current_streak(&self, today: u64) -> u32{
+		match self.last_solved{
+			Some(day) if day + 1 >= today => self.streak,
+			_ => 0,
+		}
+	}
+
+	pub fn solve(&mut self, day: u64){
+		if self.is_solved(day){
+			return;
+		}
+		self.streak = if self.last_solved.is_some_and(|last|last + 1 == day) { self.streak + 1 } else { 1 };
+		self.best_streak = self.best_streak.max(self.streak);
+		self.solved += 1;
+		self.last_solved = Some(day);
+	}
+}
+
+pub fn today() -> u64{
+	SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d|d.as_secs() / SECONDS_PER_DAY)
+}
+
+// easy at the start of the week, hard at its end
+fn difficulty(day: u64) -> Difficulty{
+	// 1970-01-01 was a thursday
+	match (day + 3) % 7{
+		0..=1 => Difficulty::Easy,
+		2..=4 => Difficulty::Medium,
+		_ => Difficulty::Hard,
+	}
+}
+
+// everyone gets the same puzzle for a day, it only depends on the date
+pub fn daily_level(day: u64) -> Option<Level>{
+	let difficulty = difficulty(day);
+	Generator::generate_bounded(day, difficulty, None, 1).map(|mut level|{
+		level.name = format!("daily {}", date(day));
+		level
+	})
+}
+
+// yyyy-mm-dd of days since 1970-01-01
+pub fn date(day: u64) -> String{
+	let z = day as i64 + 719_468;
+	let era = z.div_euclid(146_097);
+	let day_of_era = z - era * 146_097;
+	let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let month_index = (5 * day_of_year + 2) / 153;
+	let day_of_month = day_of_year - (153 * month_index + 2) / 5 + 1;
+	let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+	let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+	format!("{}-{:02}-{:02}", year, month, day_of_month)
+}
+
+#[cfg(test)]
+mod tests{
+	use super::*;
+
+	#[test]
+	fn days_are_calendar_dates(){
+		assert_eq!(date(0), "1970-01-01");
+		assert_eq!(date(59), "1970-03-01");
+		assert_eq!(date(11_016), "2000-02-29");
+		assert_eq!(date(20_744), "2026-10-18");
+		// 2100 isn't a leap year
+		assert_eq!(date(47_541), "2100-03-01");
+	}
+
+	#[test]
+	fn a_streak_counts_consecutive_days(){
+		let mut progress = DailyProgress::default();
+		progress.solve(10);
+		progress.solve(11);
+		progress.solve(11);
+		assert_eq!((progress.streak, progress.solved), (2, 2));
+		assert_eq!(progress.current_streak(12), 2);
+		assert_eq!(progress.current_streak(13), 0);
+		progress.solve(13);
+		assert_eq!((progress.streak, progress.best_streak, progress.solved), (1, 2, 3));
+	}
+
+	#[test]
+	fn everyone_gets_the_same_puzzle(){
+		let (first, second) = (daily_level(20_744).unwrap(), daily_level(20_744).unwrap());
+		assert_eq!(first.name, "daily 2026-10-18");
+		assert_eq!((first.values, first.flagged), (second.values, second.flagged));
+	}
+}
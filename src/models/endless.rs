@@ -0,0 +1,78 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::generator::{Difficulty, Generator, Pressure};
+use crate::models::level::Level;
+
+// boards cleared before the pressure goes up another step
+const BOARDS_PER_STEP: u32 = 3;
+
+// a run of generated boards that ends with the first loss
+pub struct Endless{
+	seed: u64,
+	cleared: u32,
+}
+
+#[allow(dead_code)]
+impl Endless{
+	pub fn new() -> Endless{
+		let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d|d.as_secs());
+		Endless::from_seed(seed)
+	}
+
+	pub fn from_seed(seed: u64) -> Endless{
+		Endless{
+			seed,
+			cleared: 0,
+		}
+	}
+
+	pub fn cleared(&self) -> u32{
+		self.cleared
+	}
+
+	pub fn clear(&mut self){
+		self.cleared += 1;
+	}
+
+	// the board after the ones already cleared
+	pub fn next_level(&self) -> Option<Level>{
+		let (difficulty, pressure) = stage(self.cleared);
+		let number = self.cleared.saturating_add(1).min(u32::from(u8::MAX)) as u8;
+		let key = self.seed.wrapping_add(u64::from(self.cleared));
+		Generator::generate_bounded(key, difficulty, Some(pressure), number).map(|mut level|{
+			level.name = format!("endless {}", self.cleared + 1);
+			level
+		})
+	}
+}
+
+// more tiles to choose from, fewer spare moves and more given marks every few boards
+pub fn stage(cleared: u32) -> (Difficulty, Pressure){
+	let step = cleared / BOARDS_PER_STEP;
+	let difficulty = match step{
+		0 => Difficulty::Easy,
+		1..=2 => Difficulty::Medium,
+		_ => Difficulty::Hard,
+	};
+	let pressure = Pressure{
+		enabled: (0.5 + 0.1 * f64::from(step)).min(1.0),
+		given_marks: (0.1 * f64::from(step.saturating_sub(1))).min(0.5),
+		spare_moves: 2u32.saturating_sub(step) as u8,
+	};
+	(difficulty, pressure)
+}
+
+#[cfg(test)]
+mod tests{
+	use super::*;
+
+	#[test]
+	fn every_stage_finds_a_board_within_the_budget(){
+		let mut endless = Endless::from_seed(7);
+		for _ in 0..30{
+			let level = endless.next_level().unwrap_or_else(||panic!("no board after {} cleared", endless.cleared()));
+			assert_eq!(level.name, format!("endless {}", endless.cleared() + 1));
+			endless.clear();
+		}
+	}
+}
@@ -0,0 +1,306 @@
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg32;
+
+use crate::models::board::{self, Board, Overflow, Rounds, TileKind, MAXNUMBER, ROUNDS};
+use crate::models::level::{Level, DEFAULT_SIZE};
+use crate::models::solver::{solve, SolverReport};
+use crate::tile::TileState;
+
+const MAX_ATTEMPTS: usize = 2000;
+// boards the game waits for try a few seeds with a small budget each instead
+// of one seed with the whole budget
+const MAX_SEEDS: u64 = 4;
+const ATTEMPTS_PER_SEED: usize = 250;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+	Easy,
+	Medium,
+	Hard,
+}
+
+impl Difficulty {
+	pub fn parse(s: &str) -> Option<Difficulty> {
+		match s {
+			"easy" => Some(Difficulty::Easy),
+			"medium" => Some(Difficulty::Medium),
+			"hard" => Some(Difficulty::Hard),
+			_ => None,
+		}
+	}
+
+	fn accepts(self, score: u8) -> bool{
+		match self{
+			Difficulty::Easy => score <= 3,
+			Difficulty::Medium => (4..=6).contains(&score),
+			Difficulty::Hard => score >= 7,
+		}
+	}
+
+	// how many marks the player has to find
+	fn marks(self) -> (usize, usize){
+		match self{
+			Difficulty::Easy => (1, 2),
+			Difficulty::Medium => (2, 3),
+			Difficulty::Hard => (3, 5),
+		}
+	}
+}
+
+// more marks, fewer ways to win and given marks all make a level harder
+pub fn difficulty_score(level: &Level, report: &SolverReport) -> u8{
+	let min_marks = report.min_marks().unwrap_or(0) as u8;
+	let uniqueness = match report.solutions.len(){
+		1 => 2,
+		2..=3 => 1,
+		_ => 0,
+	};
+	let presets = level.flagged.iter().filter(|&&f|f != TileState::Normal).count() as u8;
+	min_marks * 2 + uniqueness + presets
+}
+
+// overrides how many tiles can be marked, how many marks are given and how
+// many moves are spare, instead of what the difficulty picks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pressure{
+	// chance a tile that takes part in the solution can be marked
+	pub enabled: f64,
+	// chance a mark is given instead of left to the player
+	pub given_marks: f64,
+	pub spare_moves: u8,
+}
+
+pub struct Generator{
+	randomizer: Pcg32,
+	width: usize,
+	height: usize,
+	diagonals: bool,
+	rounds: Rounds,
+	max_value: u8,
+	overflow: Overflow,
+	pressure: Option<Pressure>,
+	max_attempts: usize,
+}
+
+#[allow(dead_code)]
+impl Generator{
+	pub fn new(seed: u64) -> Generator{
+		Generator{
+			randomizer: Pcg32::seed_from_u64(seed),
+			width: DEFAULT_SIZE,
+			height: DEFAULT_SIZE,
+			diagonals: false,
+			rounds: Rounds::default(),
+			max_value: MAXNUMBER,
+			overflow: Overflow::default(),
+			pressure: None,
+			max_attempts: MAX_ATTEMPTS,
+		}
+	}
+
+	pub fn set_size(self, width: usize, height: usize) -> Self{
+		Generator { width, height, ..self}
+	}
+
+	pub fn set_diagonals(self, diagonals: bool) -> Self{
+		Generator { diagonals, ..self}
+	}
+
+	pub fn set_rounds(self, rounds: Rounds) -> Self{
+		Generator { rounds, ..self}
+	}
+
+	pub fn set_values(self, max_value: u8, overflow: Overflow) -> Self{
+		Generator { max_value, overflow, ..self}
+	}
+
+	pub fn set_pressure(self, pressure: Pressure) -> Self{
+		Generator { pressure: Some(pressure), ..self}
+	}
+
+	// boards tried before generate gives up
+	pub fn set_max_attempts(self, max_attempts: usize) -> Self{
+		Generator { max_attempts, ..self}
+	}
+
+	fn tiles(&self) -> usize{
+		self.width * self.height
+	}
+
+	// a level from the first of a few seeds mixed from the key that gives one,
+	// quick enough to keep a scene waiting. the same key gives the same level
+	pub fn generate_bounded(key: u64, difficulty: Difficulty, pressure: Option<Pressure>, number: u8) -> Option<Level>{
+		(0..MAX_SEEDS).find_map(|attempt|{
+			let seed = key.wrapping_mul(0x9E37_79B9_7F4A_7C15).wrapping_add(attempt);
+			Generator{ pressure, ..Generator::new(seed) }.set_max_attempts(ATTEMPTS_PER_SEED).generate(difficulty, number)
+		})
+	}
+
+	pub fn generate(&mut self, difficulty: Difficulty, number: u8) -> Option<Level>{
+		for _ in 0..self.max_attempts{
+			if let Some(level) = self.try_level(difficulty, number){
+				return Some(level);
+			}
+		}
+		None
+	}
+
+	fn try_level(&mut self, difficulty: Difficulty, number: u8) -> Option<Level>{
+		let solved = self.dissolving_board()?;
+
+		// undo random marks so the player has to find them again
+		let (min_marks, max_marks) = difficulty.marks();
+		let marks = self.randomizer.gen_range(min_marks, max_marks + 1);
+		let mut candidates = (0..self.tiles()).filter(|&i|solved[i] > 0).collect::<Vec<_>>();
+		if candidates.len() < marks{
+			return None;
+		}
+		let mut values = solved.clone();
+		let enabled = self.pressure.map_or(0.7, |p|p.enabled);
+		let mut states = solved.iter().map(|&v|v > 0 && self.randomizer.gen_bool(enabled)).collect::<Vec<_>>();
+		let mut flagged = vec![TileState::Normal; self.tiles()];
+		let mut player_marks = 0;
+		for _ in 0..marks{
+			let i = candidates.remove(self.randomizer.gen_range(0, candidates.len()));
+			let mark = if self.randomizer.gen_bool(0.5) { TileState::Plus } else { TileState::Minus };
+			let starts = (1..=self.max_value)
+				.filter(|&v|v != solved[i] && board::marked_value(v, mark, self.max_value, self.overflow) == solved[i])
+				.collect::<Vec<_>>();
+			if starts.is_empty(){
+				return None;
+			}
+			values[i] = starts[self.randomizer.gen_range(0, starts.len())];
+			let given = match self.pressure{
+				Some(pressure) => self.randomizer.gen_bool(pressure.given_marks),
+				None => difficulty == Difficulty::Hard && self.randomizer.gen_bool(0.3),
+			};
+			if given{
+				// a given mark the player can't change
+				flagged[i] = mark;
+				states[i] = false;
+			}else{
+				states[i] = true;
+				player_marks +=1;
+			}
+		}
+		if player_marks == 0{
+			return None;
+		}
+
+		let remaining_moves = match self.pressure{
+			Some(pressure) => pressure.spare_moves,
+			None if difficulty == Difficulty::Easy => self.randomizer.gen_range(0, 2),
+			None => 0,
+		};
+		let level = Level{
+			name: format!("{:?} {}", difficulty, number).to_lowercase(),
+			number,
+			width: self.width,
+			height: self.height,
+			diagonals: self.diagonals,
+			rounds: self.rounds,
+			max_value: self.max_value,
+			overflow: self.overflow,
+			values,
+			kinds: vec![TileKind::Normal; self.tiles()],
+			states,
+			flagged,
+			moves: player_marks + remaining_moves,
+			remaining_moves,
+			par: None,
+		};
+		if Board::from_level(&level).simulate_future().remaining == 0{
+			// already solved without doing anything
+			return None;
+		}
+		let report = solve(&level);
+		if report.is_solvable() && difficulty.accepts(difficulty_score(&level, &report)){
+			Some(Level{ par: report.min_moves().map(|min|min as u8), ..level })
+		}else{
+			None
+		}
+	}
+
+	// stacks random lines round by round backwards, then checks the rules agree
+	fn dissolving_board(&mut self) -> Option<Vec<u8>>{
+		let lines = board::lines(self.width, self.height, self.diagonals);
+		if lines.is_empty(){
+			return None;
+		}
+		let rounds = match self.rounds{
+			Rounds::Fixed(count) => usize::from(count),
+			Rounds::UntilStable => self.randomizer.gen_range(ROUNDS, ROUNDS * 2),
+		};
+		let mut values = vec![0; self.tiles()];
+		for _ in 0..rounds{
+			for _ in 0..self.randomizer.gen_range(0, 3){
+				let line = &lines[self.randomizer.gen_range(0, lines.len())];
+				for &i in line.tiles.iter(){
+					values[i] +=1;
+				}
+			}
+		}
+		if values.iter().all(|&v|v == 0) || values.iter().any(|&v|v > self.max_value){
+			return None;
+		}
+		let mut board = Board{
+			width: self.width,
+			height: self.height,
+			diagonals: self.diagonals,
+			rounds: self.rounds,
+			max_value: self.max_value,
+			overflow: self.overflow,
+			values: values.clone(),
+			kinds: vec![TileKind::Normal; self.tiles()],
+			enabled: vec![true; self.tiles()],
+			marks: vec![TileState::Normal; self.tiles()],
+			actions: 0,
+			remaining_moves: 0,
+		};
+		if board.simulate_future().remaining == 0{
+			Some(values)
+		}else{
+			None
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests{
+	use super::*;
+
+	const DIFFICULTIES: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+	#[test]
+	fn generated_levels_are_fair(){
+		for &difficulty in DIFFICULTIES.iter(){
+			for seed in 0..8{
+				let level = Generator::new(seed).generate(difficulty, 1).unwrap_or_else(||panic!("{:?} seed {} found no level", difficulty, seed));
+				assert!(level.check().is_empty(), "{:?} seed {}: {:?}", difficulty, seed, level.check());
+				let report = solve(&level);
+				let min = report.min_marks().unwrap_or_else(||panic!("{:?} seed {} has no solution", difficulty, seed));
+				assert!(min <= usize::from(level.moves), "{:?} seed {}", difficulty, seed);
+				assert!(difficulty.accepts(difficulty_score(&level, &report)), "{:?} seed {} is out of its band", difficulty, seed);
+			}
+		}
+	}
+
+	#[test]
+	fn the_same_seed_gives_the_same_level(){
+		for &difficulty in DIFFICULTIES.iter(){
+			let first = Generator::new(42).set_size(4, 3).generate(difficulty, 3).unwrap();
+			let second = Generator::new(42).set_size(4, 3).generate(difficulty, 3).unwrap();
+			assert_eq!((first.width, first.height, first.number), (4, 3, 3));
+			assert_eq!((&first.values, &first.states, &first.flagged), (&second.values, &second.states, &second.flagged));
+			assert_eq!((first.moves, first.remaining_moves, &first.name), (second.moves, second.remaining_moves, &second.name));
+		}
+	}
+	#[test]
+	fn bounded_generation_follows_the_key(){
+		let first = Generator::generate_bounded(5, Difficulty::Medium, None, 2).unwrap();
+		let second = Generator::generate_bounded(5, Difficulty::Medium, None, 2).unwrap();
+		assert_eq!(first.number, 2);
+		assert_eq!((&first.values, &first.flagged), (&second.values, &second.flagged));
+		assert!(Difficulty::Medium.accepts(difficulty_score(&first, &solve(&first))));
+	}
+}
@@ -1,36 +1,213 @@
 use ron::de::{from_str};
 use serde::{Serialize, Deserialize};
 use crate::tile::TileState;
+use crate::models::board::{MAXNUMBER, MAX_VALUE_LIMIT, MAX_ROUNDS, Overflow, Rounds, TileKind};
 
-#[derive(Debug, Serialize, Deserialize)]
+pub const DEFAULT_SIZE: usize = 3;
+pub const MAX_SIZE: usize = 6;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Level{
 	pub name: String,
 	pub number: u8,
+	#[serde(default = "default_size")]
+	pub width: usize,
+	#[serde(default = "default_size")]
+	pub height: usize,
+	// both diagonals dissolve like rows and columns
+	#[serde(default)]
+	pub diagonals: bool,
+	// Fixed(4) like the jam levels or UntilStable for full chain reactions
+	#[serde(default)]
+	pub rounds: Rounds,
+	#[serde(default = "default_max_value")]
+	pub max_value: u8,
+	// Wrap turns max_value into 1 and back, Clamp stops at the ends
+	#[serde(default)]
+	pub overflow: Overflow,
+	// column by column, height values per column
 	pub values: Vec<u8>,
-	#[serde(default = "default_states")]
+	// Normal or Wall, walls need a value of 0
+	#[serde(default)]
+	pub kinds: Vec<TileKind>,
+	#[serde(default)]
 	pub states: Vec<bool>,
-	#[serde(default = "default_flagged")]
+	#[serde(default)]
 	pub flagged: Vec<TileState>,
 	pub moves: u8,
 	#[serde(default)]
 	pub remaining_moves: u8,
+	// moves for three stars, given marks that stay cost none. the solver finds it when it's missing
+	#[serde(default)]
+	pub par: Option<u8>,
 }
 
-pub fn load_level(path: &str) -> Level{
-	match from_str(path){
-		Ok(level) => level,
-		Err(error) => {
-			println!("Failed to load level: {}", error);
-			std::process::exit(1);
+#[allow(dead_code)]
+impl Level{
+	// an empty board to start the editor with
+	pub fn blank(number: u8, width: usize, height: usize) -> Level{
+		let tiles = width * height;
+		Level{
+			name: format!("level {}", number),
+			number,
+			width,
+			height,
+			diagonals: false,
+			rounds: Rounds::default(),
+			max_value: MAXNUMBER,
+			overflow: Overflow::default(),
+			values: vec![0; tiles],
+			kinds: vec![TileKind::Normal; tiles],
+			states: vec![true; tiles],
+			flagged: vec![TileState::Normal; tiles],
+			moves: 1,
+			remaining_moves: 0,
+			par: None,
+		}
+	}
+
+	// keeps the tiles that still fit, new ones are empty
+	pub fn resized(&self, width: usize, height: usize) -> Level{
+		let mut level = Level{
+			name: self.name.clone(),
+			..Level::blank(self.number, width, height)
+		};
+		level.diagonals = self.diagonals;
+		level.rounds = self.rounds;
+		level.max_value = self.max_value;
+		level.overflow = self.overflow;
+		level.moves = self.moves;
+		level.remaining_moves = self.remaining_moves;
+		level.par = self.par;
+		for x in 0..width.min(self.width){
+			for y in 0..height.min(self.height){
+				let (from, to) = (x * self.height + y, x * height + y);
+				level.values[to] = self.values[from];
+				level.kinds[to] = self.kinds[from];
+				level.states[to] = self.states[from];
+				level.flagged[to] = self.flagged[from];
+			}
+		}
+		level
+	}
+
+	pub fn tiles(&self) -> usize{
+		self.width * self.height
+	}
+
+	// everything that would make the game panic or the level unfair
+	pub fn check(&self) -> Vec<String>{
+		let mut errors = vec![];
+		if self.width == 0 || self.height == 0 || self.width > MAX_SIZE || self.height > MAX_SIZE{
+			errors.push(format!("size {}x{} is not between 1x1 and {}x{}", self.width, self.height, MAX_SIZE, MAX_SIZE));
+		}
+		let tiles = self.tiles();
+		if self.values.len() != tiles{
+			errors.push(format!("values has {} entries, expected {}", self.values.len(), tiles));
+		}
+		if self.kinds.len() != tiles{
+			errors.push(format!("kinds has {} entries, expected {}", self.kinds.len(), tiles));
+		}
+		if self.states.len() != tiles{
+			errors.push(format!("states has {} entries, expected {}", self.states.len(), tiles));
+		}
+		if self.flagged.len() != tiles{
+			errors.push(format!("flagged has {} entries, expected {}", self.flagged.len(), tiles));
 		}
+		if self.max_value == 0 || self.max_value > MAX_VALUE_LIMIT{
+			errors.push(format!("max_value {} is not between 1 and {}", self.max_value, MAX_VALUE_LIMIT));
+		}
+		for (i,value) in self.values.iter().enumerate(){
+			if *value > self.max_value{
+				errors.push(format!("value {} at tile {} is above {}", value, i, self.max_value));
+			}
+		}
+		for (i,(value,mark)) in self.values.iter().zip(self.flagged.iter()).enumerate(){
+			if *mark != TileState::Normal && *value == 0{
+				errors.push(format!("flagged {:?} at tile {} has no value to change", mark, i));
+			}
+		}
+		if let Rounds::Fixed(count) = self.rounds{
+			if usize::from(count) > MAX_ROUNDS{
+				errors.push(format!("{} rounds is above {}", count, MAX_ROUNDS));
+			}
+		}
+		for (i,kind) in self.kinds.iter().enumerate(){
+			if *kind == TileKind::Wall && (self.values.get(i) != Some(&0) || self.flagged.get(i).is_some_and(|&f|f != TileState::Normal)){
+				errors.push(format!("wall at tile {} needs value 0 and no flagged mark", i));
+			}
+		}
+		if self.remaining_moves > self.moves{
+			errors.push(format!("remaining_moves {} is above moves {}", self.remaining_moves, self.moves));
+		}
+		if let Some(par) = self.par{
+			if par > self.moves{
+				errors.push(format!("par {} is above moves {}", par, self.moves));
+			}
+		}
+		errors
+	}
+
+	// same layout as the hand made files in assets/levels
+	pub fn to_ron(&self) -> String{
+		let join = |items: Vec<String>| items.join(",");
+		let mut options = String::new();
+		if self.width != DEFAULT_SIZE || self.height != DEFAULT_SIZE{
+			options += &format!("\twidth: {},\n\theight: {},\n", self.width, self.height);
+		}
+		if self.diagonals{
+			options += "\tdiagonals: true,\n";
+		}
+		if self.rounds != Rounds::default(){
+			options += &format!("\trounds: {:?},\n", self.rounds);
+		}
+		if self.max_value != MAXNUMBER{
+			options += &format!("\tmax_value: {},\n", self.max_value);
+		}
+		if self.overflow != Overflow::default(){
+			options += &format!("\toverflow: {:?},\n", self.overflow);
+		}
+		let kinds = if self.kinds.iter().any(|&k|k != TileKind::Normal){
+			format!("\tkinds: [{}],\n", join(self.kinds.iter().map(|k|format!("{:?}", k)).collect()))
+		}else{
+			String::new()
+		};
+		let par = self.par.map_or(String::new(), |par|format!("\tpar: Some({}),\n", par));
+		format!("(\n\tname: {:?},\n\tnumber: {},\n{}\tvalues: [{}],\n{}\tstates: [{}],\n\tflagged: [{}],\n\tmoves: {},\n\tremaining_moves: {},\n{})\n",
+			self.name,
+			self.number,
+			options,
+			join(self.values.iter().map(|v|v.to_string()).collect()),
+			kinds,
+			join(self.states.iter().map(|s|s.to_string()).collect()),
+			join(self.flagged.iter().map(|f|format!("{:?}", f)).collect()),
+			self.moves,
+			self.remaining_moves,
+			par,
+		)
+	}
+}
+
+pub fn parse_level(content: &str) -> Result<Level, ron::de::Error>{
+	let mut level: Level = from_str(content)?;
+	// kinds, states and flagged are optional and follow the declared size
+	if level.kinds.is_empty(){
+		level.kinds = vec![TileKind::Normal; level.tiles()];
+	}
+	if level.states.is_empty(){
+		level.states = vec![true; level.tiles()];
+	}
+	if level.flagged.is_empty(){
+		level.flagged = vec![TileState::Normal; level.tiles()];
 	}
+	Ok(level)
 }
 
 // default values
-fn default_flagged() -> Vec<TileState>{
-	vec![TileState::Normal; 9]
+fn default_size() -> usize{
+	DEFAULT_SIZE
 }
 
-fn default_states() -> Vec<bool>{
-	vec![true; 9]
+fn default_max_value() -> u8{
+	MAXNUMBER
 }
\ No newline at end of file
@@ -1,3 +1,16 @@
 pub mod level;
 pub mod config;
-pub mod crossover;
\ No newline at end of file
+pub mod crossover;
+pub mod board;
+pub mod solver;
+pub mod generator;
+pub mod replay;
+pub mod save;
+pub mod pack;
+pub mod code;
+pub mod rating;
+pub mod time_attack;
+pub mod daily;
+pub mod endless;
+pub mod stats;
+pub mod achievement;
@@ -0,0 +1,239 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use ron::de::from_str;
+use serde::{Serialize, Deserialize};
+use tetra::graphics::Color;
+use zip::ZipArchive;
+
+use crate::models::level::{Level, parse_level};
+
+pub const MANIFEST_FILE: &str = "pack.ron";
+pub const DEFAULT_PACK: &str = "will it dissolve";
+// levels saved in the editor are played as a pack of their own
+pub const EDITOR_PACK: &str = "editor";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackManifest{
+	pub name: String,
+	pub author: String,
+	pub version: String,
+	// level files in the order they are played, numbers follow this order
+	pub levels: Vec<String>,
+	#[serde(default)]
+	pub theme: Theme,
+	// a sound file inside the pack replacing the menu music
+	#[serde(default)]
+	pub music: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Theme{
+	#[serde(default)]
+	pub background: Option<(u8, u8, u8)>,
+	#[serde(default)]
+	pub future: Option<(u8, u8, u8)>,
+}
+
+#[allow(dead_code)]
+impl Theme{
+	pub fn background(&self, default: Color) -> Color{
+		self.background.map_or(default, |(r, g, b)|Color::rgb8(r, g, b))
+	}
+
+	pub fn future(&self, default: Color) -> Color{
+		self.future.map_or(default, |(r, g, b)|Color::rgb8(r, g, b))
+	}
+}
+
+pub struct Pack{
+	pub manifest: PackManifest,
+	pub levels: Vec<Level>,
+	pub music: Option<Vec<u8>>,
+}
+
+#[allow(dead_code)]
+impl Pack{
+	// levels that don't come from a pack directory
+	pub fn custom(name: &str, levels: Vec<Level>) -> Pack{
+		Pack{
+			manifest: PackManifest{
+				name: name.to_string(),
+				author: String::new(),
+				version: String::new(),
+				levels: vec![],
+				theme: Theme::default(),
+				music: None,
+			},
+			levels,
+			music: None,
+		}
+	}
+
+	// the levels that came with the game
+	pub fn jam(levels: Vec<Level>) -> Pack{
+		let mut pack = Pack::custom(DEFAULT_PACK, levels);
+		pack.manifest.author = "puppetmaster".to_string();
+		pack.manifest.version = env!("CARGO_PKG_VERSION").to_string();
+		pack
+	}
+
+	pub fn name(&self) -> &str{
+		&self.manifest.name
+	}
+
+	pub fn theme(&self) -> Theme{
+		self.manifest.theme
+	}
+}
+
+// a pack is a directory or a zip archive with pack.ron and the level files at its root
+enum PackFiles{
+	Directory(PathBuf),
+	Archive(ZipArchive<File>),
+}
+
+impl PackFiles{
+	fn open(path: &Path) -> Result<PackFiles, String>{
+		if path.is_dir(){
+			return Ok(PackFiles::Directory(path.to_path_buf()));
+		}
+		let file = File::open(path).map_err(|e|e.to_string())?;
+		ZipArchive::new(file).map(PackFiles::Archive).map_err(|e|e.to_string())
+	}
+
+	fn read(&mut self, name: &str) -> Result<Vec<u8>, String>{
+		match self{
+			PackFiles::Directory(directory) => fs::read(directory.join(name)).map_err(|e|format!("{}: {}", name, e)),
+			PackFiles::Archive(archive) => {
+				let mut file = archive.by_name(name).map_err(|e|format!("{}: {}", name, e))?;
+				let mut data = vec![];
+				file.read_to_end(&mut data).map_err(|e|format!("{}: {}", name, e))?;
+				Ok(data)
+			}
+		}
+	}
+
+	fn read_string(&mut self, name: &str) -> Result<String, String>{
+		String::from_utf8(self.read(name)?).map_err(|_|format!("{} is not utf-8", name))
+	}
+}
+
+pub fn is_pack(path: &Path) -> bool{
+	path.join(MANIFEST_FILE).is_file() || path.extension().is_some_and(|extension|extension == "zip")
+}
+
+// broken levels are reported and skipped like loose level files
+pub fn load_pack(path: &Path) -> Result<Pack, String>{
+	let mut files = PackFiles::open(path)?;
+	let manifest: PackManifest = from_str(&files.read_string(MANIFEST_FILE)?)
+		.map_err(|e|format!("{}: {}", MANIFEST_FILE, e))?;
+	// levels are numbered with a u8
+	if manifest.levels.len() > usize::from(u8::MAX){
+		return Err(format!("pack \"{}\" has {} levels, at most {} fit", manifest.name, manifest.levels.len(), u8::MAX));
+	}
+	let mut levels = vec![];
+	for file in manifest.levels.iter(){
+		let level = files.read_string(file)
+			.and_then(|content|parse_level(&content).map_err(|e|format!("{}: {}", file, e)))
+			.and_then(|level|match level.check(){
+				errors if errors.is_empty() => Ok(level),
+				errors => Err(format!("{}: {}", file, errors.join(", "))),
+			});
+		match level{
+			Ok(mut level) => {
+				level.number = levels.len() as u8 + 1;
+				levels.push(level);
+			}
+			Err(error) => log::warn!("pack \"{}\" skips {}", manifest.name, error),
+		}
+	}
+	if levels.is_empty(){
+		return Err(format!("pack \"{}\" has no playable level", manifest.name));
+	}
+	let music = match &manifest.music{
+		Some(file) => match files.read(file){
+			Ok(data) => Some(data),
+			Err(error) => {
+				log::warn!("pack \"{}\" plays the default music, {}", manifest.name, error);
+				None
+			}
+		},
+		None => None,
+	};
+	Ok(Pack{
+		manifest,
+		levels,
+		music,
+	})
+}
+
+#[cfg(test)]
+mod tests{
+	use super::*;
+	use std::io::Write;
+	use zip::ZipWriter;
+	use zip::write::FileOptions;
+	use crate::managers::levelmanager::LEVEL_FILES;
+	use crate::utils::storage::scratch_dir;
+
+	fn manifest(levels: &[&str]) -> String{
+		let levels = levels.iter().map(|file|format!("\"{}\"", file)).collect::<Vec<_>>().join(", ");
+		format!("(name: \"test\", author: \"tester\", version: \"1\", levels: [{}])", levels)
+	}
+
+	// the second jam level goes first and a missing file is skipped
+	fn pack_files() -> Vec<(&'static str, String)>{
+		vec![
+			(MANIFEST_FILE, manifest(&["b.ron", "missing.ron", "a.ron"])),
+			("a.ron", LEVEL_FILES[0].1.to_string()),
+			("b.ron", LEVEL_FILES[1].1.to_string()),
+		]
+	}
+
+	fn assert_test_pack(pack: &Pack){
+		assert_eq!(pack.name(), "test");
+		assert_eq!(pack.levels.len(), 2);
+		assert_eq!(pack.levels.iter().map(|l|l.number).collect::<Vec<_>>(), vec![1, 2]);
+		assert_eq!(pack.levels[0].values, parse_level(LEVEL_FILES[1].1).unwrap().values);
+		assert!(pack.music.is_none());
+	}
+
+	#[test]
+	fn a_directory_pack_numbers_its_levels_in_order(){
+		let dir = scratch_dir("directory_pack");
+		for (name, content) in pack_files(){
+			fs::write(dir.join(name), content).unwrap();
+		}
+		assert!(is_pack(&dir));
+		assert_test_pack(&load_pack(&dir).unwrap());
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn a_zip_pack_loads_like_a_directory(){
+		let dir = scratch_dir("zip_pack");
+		let path = dir.join("test.zip");
+		let mut zip = ZipWriter::new(File::create(&path).unwrap());
+		for (name, content) in pack_files(){
+			zip.start_file(name, FileOptions::default()).unwrap();
+			zip.write_all(content.as_bytes()).unwrap();
+		}
+		zip.finish().unwrap();
+		assert!(is_pack(&path));
+		assert_test_pack(&load_pack(&path).unwrap());
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn packs_without_levels_or_with_too_many_are_refused(){
+		let dir = scratch_dir("refused_pack");
+		fs::write(dir.join(MANIFEST_FILE), manifest(&["missing.ron"])).unwrap();
+		assert!(load_pack(&dir).is_err());
+		let files = (0..=usize::from(u8::MAX)).map(|n|format!("level_{}.ron", n)).collect::<Vec<_>>();
+		fs::write(dir.join(MANIFEST_FILE), manifest(&files.iter().map(|f|f.as_str()).collect::<Vec<_>>())).unwrap();
+		assert!(matches!(load_pack(&dir), Err(error) if error.contains("256 levels")));
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
@@ -0,0 +1,82 @@
+use crate::models::level::Level;
+use crate::models::solver::{solve_within, MAX_NODES};
+
+pub const MAX_STARS: u8 = 3;
+
+// the par from the level file or the fewest moves the solver finds, counted like
+// the moves of a win. a board too big to search gets three stars for any win
+pub fn par(level: &Level) -> u8{
+	level.par.unwrap_or_else(||{
+		let report = solve_within(level, MAX_NODES);
+		match report.min_moves(){
+			Some(min) if report.complete => min as u8,
+			_ => level.moves,
+		}
+	})
+}
+
+// three stars at par, two up to halfway to the whole budget, one for any other win
+pub fn stars(marks_used: u8, par: u8, moves: u8) -> u8{
+	if marks_used <= par{
+		MAX_STARS
+	}else if marks_used <= par + moves.saturating_sub(par).div_ceil(2){
+		2
+	}else{
+		1
+	}
+}
+
+#[cfg(test)]
+mod tests{
+	use super::*;
+	use crate::managers::levelmanager::embedded_levels;
+	use crate::models::board::Board;
+	use crate::models::solver::solve;
+	use crate::tile::TileState;
+
+	#[test]
+	fn stars_drop_towards_the_whole_budget(){
+		assert_eq!(stars(2, 2, 6), MAX_STARS);
+		assert_eq!(stars(1, 2, 6), MAX_STARS);
+		assert_eq!(stars(3, 2, 6), 2);
+		assert_eq!(stars(4, 2, 6), 2);
+		assert_eq!(stars(5, 2, 6), 1);
+		assert_eq!(stars(6, 2, 6), 1);
+	}
+
+	#[test]
+	fn a_budget_at_par_only_has_three_stars(){
+		assert_eq!(stars(3, 3, 3), MAX_STARS);
+		assert_eq!(stars(4, 3, 3), 1);
+		assert_eq!(stars(255, 255, 255), MAX_STARS);
+		assert_eq!(stars(255, 254, 255), 2);
+	}
+
+	#[test]
+	fn par_comes_from_the_file_first(){
+		let mut level = embedded_levels().remove(0);
+		let solved = par(&level);
+		assert!(solved >= 1 && solved <= level.moves);
+		level.par = Some(solved + 1);
+		assert_eq!(par(&level), solved + 1);
+	}
+
+	// a kept preset mark costs no move, so it doesn't count towards the par either
+	#[test]
+	fn par_counts_moves_like_a_win(){
+		let mut level = Level::blank(1, 3, 3);
+		level.moves = 1;
+		level.remaining_moves = 1;
+		level.values = vec![1, 2, 0, 1, 2, 0, 2, 1, 0];
+		level.states = vec![false, false, false, false, false, false, true, true, false];
+		level.flagged[6] = TileState::Minus;
+		assert!(level.check().is_empty(), "{:?}", level.check());
+		assert_eq!(solve(&level).min_marks(), Some(2));
+		assert_eq!(par(&level), 1);
+		let mut board = Board::from_level(&level);
+		assert!(board.toggle_mark(7, TileState::Plus));
+		let moves_used = level.moves - board.actions;
+		assert_eq!(board.simulate_future().remaining, 0);
+		assert_eq!(stars(moves_used, par(&level), level.moves), MAX_STARS);
+	}
+}
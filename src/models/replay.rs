@@ -0,0 +1,98 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ron::de::from_str;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::{Serialize, Deserialize};
+
+use crate::models::level::Level;
+use crate::models::pack::DEFAULT_PACK;
+use crate::tile::TileState;
+
+// one attempt at a level, times are milliseconds since the level started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay{
+	// replays from before packs existed belong to the built-in levels
+	#[serde(default = "default_pack")]
+	pub pack: String,
+	pub level: u8,
+	pub name: String,
+	pub events: Vec<ReplayEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayEvent{
+	pub time: u64,
+	pub action: ReplayAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplayAction{
+	Mark(usize, TileState),
+	Unmark(usize),
+	Undo,
+	Redo,
+	Future,
+}
+
+fn default_pack() -> String{
+	DEFAULT_PACK.to_string()
+}
+
+impl Replay{
+	pub fn new(pack: &str, level: &Level) -> Replay{
+		Replay{
+			pack: pack.to_string(),
+			level: level.number,
+			name: level.name.clone(),
+			events: vec![],
+		}
+	}
+
+	pub fn push(&mut self, time: u64, action: ReplayAction){
+		self.events.push(ReplayEvent{ time, action });
+	}
+
+	pub fn matches(&self, level: &Level) -> bool{
+		self.level == level.number && self.name == level.name
+	}
+
+	// the first event that points past the board, replays are plain files anyone can edit
+	pub fn out_of_bounds(&self, tiles: usize) -> Option<&ReplayEvent>{
+		self.events.iter().find(|event|match event.action{
+			ReplayAction::Mark(index, _) | ReplayAction::Unmark(index) => index >= tiles,
+			_ => false,
+		})
+	}
+
+	// writes level_<number>_<unix time>.ron into the directory
+	pub fn save(&self, directory: &Path) -> Result<PathBuf, String>{
+		fs::create_dir_all(directory).map_err(|e|e.to_string())?;
+		let time = SystemTime::now().duration_since(UNIX_EPOCH).map(|d|d.as_millis()).unwrap_or(0);
+		let path = directory.join(format!("level_{}_{}.ron", self.level, time));
+		let content = to_string_pretty(self, PrettyConfig::default()).map_err(|e|e.to_string())?;
+		fs::write(&path, content).map_err(|e|e.to_string())?;
+		Ok(path)
+	}
+}
+
+pub fn load_replay(path: &Path) -> Result<Replay, String>{
+	let content = fs::read_to_string(path).map_err(|e|e.to_string())?;
+	from_str(&content).map_err(|e|e.to_string())
+}
+
+#[cfg(test)]
+mod tests{
+	use super::*;
+
+	#[test]
+	fn events_past_the_board_are_found(){
+		let mut replay = Replay::new(DEFAULT_PACK, &Level::blank(1, 3, 3));
+		replay.push(0, ReplayAction::Mark(8, TileState::Plus));
+		replay.push(10, ReplayAction::Future);
+		assert!(replay.out_of_bounds(9).is_none());
+		replay.push(20, ReplayAction::Unmark(9));
+		assert_eq!(replay.out_of_bounds(9).map(|event|event.time), Some(20));
+	}
+}
@@ -0,0 +1,241 @@
+use std::path::PathBuf;
+
+use ron::de::from_str;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::{Serialize, Deserialize};
+
+use crate::models::pack::DEFAULT_PACK;
+use crate::models::time_attack::{TimeAttackRun, BEST_TIMES};
+use crate::models::daily::DailyProgress;
+use crate::models::stats::LevelStats;
+use crate::utils::storage;
+
+const SAVE_FILE: &str = "save.ron";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveGame{
+	// the pack the menu starts
+	#[serde(default = "default_pack")]
+	pub pack: String,
+	#[serde(default)]
+	pub packs: Vec<PackProgress>,
+	// the daily puzzle doesn't belong to a pack
+	#[serde(default)]
+	pub daily: DailyProgress,
+	// most generated boards cleared in one endless run
+	#[serde(default)]
+	pub best_endless: u32,
+	// ids of the unlocked achievements
+	#[serde(default)]
+	pub achievements: Vec<String>,
+	// saves from before packs kept the campaign at the top level
+	#[serde(default, skip_serializing, rename = "highest_unlocked")]
+	legacy_highest_unlocked: u8,
+	#[serde(default, skip_serializing, rename = "levels")]
+	legacy_levels: Vec<LevelProgress>,
+}
+
+// level numbers only mean something inside their pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackProgress{
+	pub pack: String,
+	// level number the campaign continues with
+	pub highest_unlocked: u8,
+	#[serde(default)]
+	pub levels: Vec<LevelProgress>,
+	// fastest time attack runs first
+	#[serde(default)]
+	pub best_times: Vec<TimeAttackRun>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelProgress{
+	pub number: u8,
+	pub completed: bool,
+	// fewest marks used to win
+	pub best_moves: Option<u8>,
+	#[serde(default)]
+	pub stars: u8,
+	#[serde(default)]
+	pub stats: LevelStats,
+}
+
+impl LevelProgress{
+	fn new(number: u8) -> LevelProgress{
+		LevelProgress{
+			number,
+			completed: false,
+			best_moves: None,
+			stars: 0,
+			stats: LevelStats::default(),
+		}
+	}
+}
+
+fn default_pack() -> String{
+	DEFAULT_PACK.to_string()
+}
+
+#[allow(dead_code)]
+impl SaveGame{
+	fn path() -> Option<PathBuf>{
+		storage::data_dir().map(|dir|dir.join(SAVE_FILE))
+	}
+
+	// a missing or broken save starts a new campaign
+	pub fn load() -> SaveGame{
+		Self::path()
+			.and_then(|path|storage::read_with_backup(&path, SaveGame::parse))
+			.unwrap_or_else(|| SaveGame{ pack: default_pack(), ..SaveGame::default() })
+	}
+
+	// the old campaign becomes the progress of the built-in pack
+	fn parse(content: &str) -> Option<SaveGame>{
+		let mut save: SaveGame = from_str(content).ok()?;
+		let legacy_levels = std::mem::take(&mut save.legacy_levels);
+		if (save.legacy_highest_unlocked > 0 || !legacy_levels.is_empty()) && save.pack_progress(DEFAULT_PACK).is_none(){
+			save.packs.push(PackProgress{
+				pack: DEFAULT_PACK.to_string(),
+				highest_unlocked: save.legacy_highest_unlocked.max(1),
+				levels: legacy_levels,
+				best_times: vec![],
+			});
+		}
+		save.legacy_highest_unlocked = 0;
+		Some(save)
+	}
+
+	pub fn save(&self){
+		let path = match Self::path(){
+			Some(path) => path,
+			None => return,
+		};
+		let result = to_string_pretty(self, PrettyConfig::default())
+			.map_err(|e|e.to_string())
+			.and_then(|content|storage::write_atomic(&path, &content).map_err(|e|e.to_string()));
+		if let Err(error) = result{
+			log::warn!("can't write save game {}: {}", path.display(), error);
+		}
+	}
+
+	pub fn pack_progress(&self, pack: &str) -> Option<&PackProgress>{
+		self.packs.iter().find(|p|p.pack == pack)
+	}
+
+	fn current(&self) -> Option<&PackProgress>{
+		self.pack_progress(&self.pack)
+	}
+
+	fn current_mut(&mut self) -> &mut PackProgress{
+		match self.packs.iter().position(|p|p.pack == self.pack){
+			Some(index) => &mut self.packs[index],
+			None => {
+				self.packs.push(PackProgress{ pack: self.pack.clone(), highest_unlocked: 1, levels: vec![], best_times: vec![] });
+				self.packs.last_mut().unwrap()
+			}
+		}
+	}
+
+	pub fn highest_unlocked(&self) -> u8{
+		self.current().map_or(1, |p|p.highest_unlocked)
+	}
+
+	pub fn has_progress(&self) -> bool{
+		self.highest_unlocked() > 1 || self.current().is_some_and(|p|p.levels.iter().any(|l|l.completed))
+	}
+
+	pub fn progress(&self, number: u8) -> Option<&LevelProgress>{
+		self.current()?.levels.iter().find(|l|l.number == number)
+	}
+
+	pub fn progress_mut(&mut self, number: u8) -> &mut LevelProgress{
+		let levels = &mut self.current_mut().levels;
+		match levels.iter().position(|l|l.number == number){
+			Some(index) => &mut levels[index],
+			None => {
+				levels.push(LevelProgress::new(number));
+				levels.last_mut().unwrap()
+			}
+		}
+	}
+
+	pub fn is_completed(&self, number: u8) -> bool{
+		self.progress(number).is_some_and(|l|l.completed)
+	}
+
+	pub fn is_unlocked(&self, number: u8) -> bool{
+		number <= self.highest_unlocked() || self.is_completed(number)
+	}
+
+	pub fn completed_levels(&self) -> u32{
+		self.current().map_or(0, |p|p.levels.iter().filter(|l|l.completed).count() as u32)
+	}
+
+	pub fn has_achievement(&self, id: &str) -> bool{
+		self.achievements.iter().any(|a|a == id)
+	}
+
+	pub fn unlock_achievement(&mut self, id: &str){
+		if !self.has_achievement(id){
+			self.achievements.push(id.to_string());
+		}
+	}
+
+	pub fn best_times(&self) -> &[TimeAttackRun]{
+		self.current().map_or(&[][..], |p|&p.best_times[..])
+	}
+
+	// true for a new best score
+	pub fn add_endless(&mut self, cleared: u32) -> bool{
+		if cleared > self.best_endless{
+			self.best_endless = cleared;
+			true
+		}else{
+			false
+		}
+	}
+
+	// the place of the run in the table, if it made it
+	pub fn add_time_attack(&mut self, run: TimeAttackRun) -> Option<usize>{
+		let best_times = &mut self.current_mut().best_times;
+		let place = best_times.iter().position(|r|run.total < r.total).unwrap_or(best_times.len());
+		if place >= BEST_TIMES{
+			return None;
+		}
+		best_times.insert(place, run);
+		best_times.truncate(BEST_TIMES);
+		Some(place)
+	}
+
+	pub fn complete_level(&mut self, number: u8, moves_used: u8, stars: u8, next_level: Option<u8>){
+		let progress = self.progress_mut(number);
+		progress.completed = true;
+		progress.best_moves = Some(progress.best_moves.map_or(moves_used, |best|best.min(moves_used)));
+		progress.stars = progress.stars.max(stars);
+		if let Some(next) = next_level{
+			let pack = self.current_mut();
+			pack.highest_unlocked = pack.highest_unlocked.max(next);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn saves_from_before_packs_keep_their_progress(){
+		let save = SaveGame::parse("(highest_unlocked: 4, levels: [(number: 3, completed: true, best_moves: Some(2))])").unwrap();
+		assert_eq!(save.pack, DEFAULT_PACK);
+		assert_eq!(save.highest_unlocked(), 4);
+		assert!(save.is_completed(3));
+		assert_eq!(save.progress(3).unwrap().best_moves, Some(2));
+	}
+
+	#[test]
+	fn pack_progress_wins_over_the_old_layout(){
+		let save = SaveGame::parse("(pack: \"will it dissolve\", packs: [(pack: \"will it dissolve\", highest_unlocked: 7)], highest_unlocked: 2)").unwrap();
+		assert_eq!(save.packs.len(), 1);
+		assert_eq!(save.highest_unlocked(), 7);
+	}
+}
@@ -0,0 +1,225 @@
+use crate::models::board::Board;
+use crate::models::level::Level;
+use crate::tile::TileState;
+
+const OPTIONS: [TileState; 3] = [TileState::Normal, TileState::Plus, TileState::Minus];
+// futures a search in the game may play before it gives up, each one takes some
+// microseconds. the jam levels need a few hundred, big editor boards need billions
+pub const MAX_NODES: usize = 20_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution{
+	// the marks of the whole board right before pressing future
+	pub marks: Vec<TileState>,
+	// marks placed on tiles the player can click
+	pub count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct SolverReport{
+	pub solutions: Vec<Solution>,
+	// marks on clickable tiles before the search, they are kept without a move
+	pub given: usize,
+	// false when the search ran out of nodes, better solutions may be missing
+	pub complete: bool,
+}
+
+#[allow(dead_code)]
+impl SolverReport{
+	pub fn is_solvable(&self) -> bool{
+		!self.solutions.is_empty()
+	}
+
+	pub fn min_marks(&self) -> Option<usize>{
+		self.solutions.iter().map(|s|s.count).min()
+	}
+
+	// the moves the player spends, counted like the actions left after a win
+	pub fn min_moves(&self) -> Option<usize>{
+		self.min_marks().map(|min|min.saturating_sub(self.given))
+	}
+
+	// fewest marks first, ties keep the enumeration order
+	pub fn canonical(&self) -> Option<&Solution>{
+		let min = self.min_marks()?;
+		self.solutions.iter().find(|s|s.count == min)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint{
+	Mark(usize, TileState),
+	// the current marks already win, press future
+	Ready,
+	// no solution keeps the marks placed so far
+	Impossible,
+	// the board is too big to search, no mark was found
+	Unknown,
+}
+
+// next mark of the best solution that keeps every mark the player placed,
+// given marks on tiles that can be clicked may still have to go
+pub fn hint(board: &Board, given: &[TileState]) -> Hint{
+	let placed = (0..board.len())
+		.filter(|&i|board.is_clickable(i) && board.is_marked(i) && board.marks[i] != given[i])
+		.collect::<Vec<_>>();
+	let report = solve_board_within(board, MAX_NODES);
+	let solution = report.solutions.iter()
+		.filter(|s|placed.iter().all(|&i|s.marks[i] == board.marks[i]))
+		.min_by_key(|s|s.count);
+	match solution{
+		// a mark is removed by clicking it again
+		Some(solution) => (0..board.len())
+			.find(|&i|solution.marks[i] != board.marks[i])
+			.map_or(Hint::Ready, |i|match solution.marks[i]{
+				TileState::Normal => Hint::Mark(i, board.marks[i]),
+				mark => Hint::Mark(i, mark),
+			}),
+		None if report.complete => Hint::Impossible,
+		None => Hint::Unknown,
+	}
+}
+
+pub fn solve(level: &Level) -> SolverReport{
+	solve_board(&Board::from_level(level))
+}
+
+// the search for the game, it stops after max_nodes futures
+pub fn solve_within(level: &Level, max_nodes: usize) -> SolverReport{
+	solve_board_within(&Board::from_level(level), max_nodes)
+}
+
+// tries every mark on every clickable tile that fits into the move budget
+pub fn solve_board(board: &Board) -> SolverReport{
+	solve_board_within(board, usize::MAX)
+}
+
+pub fn solve_board_within(board: &Board, max_nodes: usize) -> SolverReport{
+	let clickable = (0..board.len()).filter(|&i|board.is_clickable(i)).collect::<Vec<_>>();
+	let already_marked = clickable.iter().filter(|&&i|board.is_marked(i)).count();
+	let budget = usize::from(board.actions) + already_marked;
+	let mut search = Search{
+		board,
+		clickable: &clickable,
+		budget,
+		marks: board.marks.clone(),
+		solutions: vec![],
+		nodes: max_nodes,
+		complete: true,
+	};
+	search.next(0, 0);
+	SolverReport{
+		solutions: search.solutions,
+		given: already_marked,
+		complete: search.complete,
+	}
+}
+
+struct Search<'a>{
+	board: &'a Board,
+	clickable: &'a [usize],
+	budget: usize,
+	marks: Vec<TileState>,
+	solutions: Vec<Solution>,
+	// futures left to play
+	nodes: usize,
+	complete: bool,
+}
+
+impl<'a> Search<'a>{
+	// bigger boards have too many combinations, so stop as soon as the budget is spent
+	fn next(&mut self, position: usize, count: usize){
+		if !self.complete{
+			return;
+		}
+		if position == self.clickable.len(){
+			if self.budget - count <= usize::from(self.board.remaining_moves){
+				if self.nodes == 0{
+					self.complete = false;
+					return;
+				}
+				self.nodes -=1;
+				let mut future = Board{
+					marks: self.marks.clone(),
+					..self.board.clone()
+				};
+				let trace = future.simulate_future();
+				if trace.remaining == 0 && !trace.capped{
+					self.solutions.push(Solution{ marks: self.marks.clone(), count });
+				}
+			}
+			return;
+		}
+		let i = self.clickable[position];
+		for &option in OPTIONS.iter(){
+			let marked = option != TileState::Normal;
+			if marked && count == self.budget{
+				continue;
+			}
+			self.marks[i] = option;
+			self.next(position + 1, if marked { count + 1 } else { count });
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests{
+	use super::*;
+	use crate::managers::levelmanager::embedded_levels;
+
+	#[test]
+	fn embedded_levels_are_solvable(){
+		for level in embedded_levels(){
+			let report = solve(&level);
+			let solution = report.canonical().unwrap_or_else(||panic!("level {} has no solution", level.number));
+			assert!(solution.count <= usize::from(level.moves), "level {}", level.number);
+			assert_eq!(report.min_marks(), Some(solution.count));
+			let mut board = Board{ marks: solution.marks.clone(), ..Board::from_level(&level) };
+			assert_eq!(board.simulate_future().remaining, 0, "level {}", level.number);
+		}
+	}
+
+	#[test]
+	fn hints_follow_the_canonical_solution(){
+		for level in embedded_levels(){
+			let mut board = Board::from_level(&level);
+			// placing every hinted mark wins the level
+			for _ in 0..=level.moves{
+				match hint(&board, &level.flagged){
+					Hint::Mark(i, state) => assert!(board.toggle_mark(i, state), "level {}", level.number),
+					Hint::Ready => break,
+					Hint::Impossible | Hint::Unknown => panic!("level {} has no hint", level.number),
+				}
+			}
+			assert_eq!(hint(&board, &level.flagged), Hint::Ready, "level {}", level.number);
+			assert_eq!(board.simulate_future().remaining, 0, "level {}", level.number);
+		}
+	}
+
+	#[test]
+	fn the_game_searches_the_jam_levels_to_the_end(){
+		for level in embedded_levels(){
+			let report = solve_within(&level, MAX_NODES);
+			assert!(report.complete, "level {}", level.number);
+			assert_eq!(report.solutions, solve(&level).solutions, "level {}", level.number);
+		}
+	}
+
+	#[test]
+	fn a_search_out_of_nodes_is_incomplete(){
+		let level = embedded_levels().remove(0);
+		let report = solve_within(&level, 0);
+		assert!(!report.complete);
+		assert!(!report.is_solvable());
+		assert!(solve_within(&level, 1).solutions.len() <= 1);
+	}
+
+	#[test]
+	fn a_board_without_clickable_tiles_has_no_solution(){
+		let mut level = Level::blank(1, 3, 3);
+		level.values[0] = 1;
+		level.states = vec![false; 9];
+		assert!(!solve(&level).is_solvable());
+		assert_eq!(hint(&Board::from_level(&level), &level.flagged), Hint::Impossible);
+	}
+}
@@ -0,0 +1,62 @@
+use serde::{Serialize, Deserialize};
+
+use crate::models::level::Level;
+use crate::models::save::SaveGame;
+
+pub const CSV_FILE: &str = "stats.csv";
+const CSV_HEADER: &str = "pack,level,name,attempts,losses,seconds,marks,hints,completed,best_moves,stars";
+
+// everything played on a level, summed over all attempts
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LevelStats{
+	pub attempts: u32,
+	pub losses: u32,
+	pub milliseconds: u64,
+	// marks placed when the future was played
+	pub marks: u32,
+	pub hints: u32,
+}
+
+#[allow(dead_code)]
+impl LevelStats{
+	// lost attempts in percent of all attempts
+	pub fn loss_rate(&self) -> Option<u32>{
+		(self.losses.min(self.attempts) * 100).checked_div(self.attempts)
+	}
+}
+
+// one row per level of every pack, levels never played have zeros
+pub fn to_csv(save: &SaveGame, packs: &[(&str, &[Level])]) -> String{
+	let mut csv = String::from(CSV_HEADER);
+	csv.push('\n');
+	for &(pack, levels) in packs.iter(){
+		for level in levels.iter(){
+			let progress = save.pack_progress(pack).and_then(|p|p.levels.iter().find(|l|l.number == level.number));
+			let stats = progress.map_or(LevelStats::default(), |p|p.stats);
+			csv.push_str(&[
+				csv_field(pack),
+				level.number.to_string(),
+				csv_field(&level.name),
+				stats.attempts.to_string(),
+				stats.losses.to_string(),
+				format!("{:.1}", stats.milliseconds as f64 / 1000.0),
+				stats.marks.to_string(),
+				stats.hints.to_string(),
+				progress.is_some_and(|p|p.completed).to_string(),
+				progress.and_then(|p|p.best_moves).map_or(String::new(), |best|best.to_string()),
+				progress.map_or(0, |p|p.stars).to_string(),
+			].join(","));
+			csv.push('\n');
+		}
+	}
+	csv
+}
+
+// names are quoted when they could break the columns
+fn csv_field(value: &str) -> String{
+	if value.contains([',', '"', '\n']){
+		format!("\"{}\"", value.replace('"', "\"\""))
+	}else{
+		value.to_string()
+	}
+}
@@ -0,0 +1,63 @@
+use std::time::Instant;
+use serde::{Serialize, Deserialize};
+
+// milliseconds added for every lost attempt
+pub const TIME_PENALTY: u64 = 10_000;
+// runs kept per pack
+pub const BEST_TIMES: usize = 5;
+
+// all levels of a pack in order against the clock
+pub struct TimeAttack{
+	start: Instant,
+	penalties: u64,
+	// clock time when the last level was won
+	last_split: u64,
+	splits: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeAttackRun{
+	pub total: u64,
+	// time per level including its penalties
+	pub splits: Vec<u64>,
+}
+
+#[allow(dead_code)]
+impl TimeAttack{
+	pub fn new() -> TimeAttack{
+		TimeAttack{
+			start: Instant::now(),
+			penalties: 0,
+			last_split: 0,
+			splits: vec![],
+		}
+	}
+
+	// the running clock with penalties
+	pub fn elapsed(&self) -> u64{
+		self.start.elapsed().as_millis() as u64 + self.penalties
+	}
+
+	pub fn penalty(&mut self){
+		self.penalties += TIME_PENALTY;
+	}
+
+	pub fn split(&mut self){
+		let now = self.elapsed();
+		self.splits.push(now - self.last_split);
+		self.last_split = now;
+	}
+
+	pub fn finish(&self) -> TimeAttackRun{
+		TimeAttackRun{
+			total: self.last_split,
+			splits: self.splits.clone(),
+		}
+	}
+}
+
+// m:ss.t
+pub fn format_time(milliseconds: u64) -> String{
+	let tenths = milliseconds / 100;
+	format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
@@ -0,0 +1,71 @@
+use tetra::graphics::{self, DrawParams, Color};
+use tetra::graphics::text::{Text, Font};
+use tetra::input::{self, Key};
+use tetra::Context;
+
+use crate::constants::*;
+
+// one item at a time with a title and details, the arrow keys go round the items
+pub struct Pager{
+	count: usize,
+	selected: usize,
+	title: Text,
+	details: Text,
+	// locked items are drawn faded
+	faded: bool,
+}
+
+#[allow(dead_code)]
+impl Pager{
+	pub fn new(count: usize) -> Pager{
+		Pager{
+			count,
+			selected: 0,
+			title: Text::new("", Font::default(), 8.0),
+			details: Text::new("", Font::default(), 8.0),
+			faded: false,
+		}
+	}
+
+	pub fn is_empty(&self) -> bool{
+		self.count == 0
+	}
+
+	pub fn get_selected(&self) -> usize{
+		self.selected
+	}
+
+	// what the owner shows for the selected item
+	pub fn set_page(&mut self, title: &str, details: &str, faded: bool){
+		self.title.set_content(title);
+		self.details.set_content(details);
+		self.faded = faded;
+	}
+
+	// returns the item that got selected this frame
+	pub fn update(&mut self, ctx: &mut Context) -> Option<usize>{
+		if self.count == 0{
+			return None;
+		}
+		if input::is_key_pressed(ctx, Key::Left) || input::is_key_pressed(ctx, Key::Up){
+			self.selected = if self.selected == 0 { self.count - 1 } else { self.selected - 1 };
+		}else if input::is_key_pressed(ctx, Key::Right) || input::is_key_pressed(ctx, Key::Down){
+			self.selected = (self.selected + 1) % self.count;
+		}else{
+			return None;
+		}
+		Some(self.selected)
+	}
+
+	pub fn draw(&self, ctx: &mut Context){
+		let color = if self.faded { Color::rgba(1.0, 1.0, 1.0, 0.3) } else { Color::rgb(1.0, 1.0, 1.0) };
+		graphics::draw(ctx, &self.title, DrawParams::new()
+			.position(GET_LEVEL_TITLE_POSITION())
+			.color(color)
+			);
+		graphics::draw(ctx, &self.details, DrawParams::new()
+			.position(GET_STATS_POSITION())
+			.color(color)
+			);
+	}
+}
@@ -0,0 +1,98 @@
+use std::rc::Rc;
+use std::cell::{RefCell};
+
+use tetra::{Context, input};
+use tetra::input::{Key};
+use tetra::graphics::{self, Drawable, DrawParams};
+use tetra::graphics::text::{Text, Font};
+
+use crate::assets::Assets;
+use crate::scenes::manager::{Scene, Transition};
+use crate::models::config::Config;
+use crate::models::save::SaveGame;
+use crate::models::achievement::{self, Achievement};
+use crate::constants::*;
+use crate::pager::Pager;
+use crate::utils::mouse::Mouse;
+
+// about as many characters as fit next to each other in the window
+const LINE_LENGTH: usize = 18;
+
+// goes through the achievements one at a time, locked ones are faded
+pub struct AchievementsScene {
+	config: Rc<Config>,
+	assets: Rc<RefCell<Assets>>,
+	save: Rc<RefCell<SaveGame>>,
+	achievements: Vec<Achievement>,
+	pager: Pager,
+	info: Text,
+	mouse: Mouse,
+}
+
+impl AchievementsScene {
+	pub fn new(_ctx: &mut Context,config: Rc<Config>, assets: Rc<RefCell<Assets>>, save: Rc<RefCell<SaveGame>>) -> tetra::Result<AchievementsScene> {
+		let achievements = achievement::load_achievements();
+		let unlocked = achievements.iter().filter(|a|save.borrow().has_achievement(&a.id)).count();
+		let mut scene = AchievementsScene {
+			mouse: Mouse::new(Rc::clone(&assets))?,
+			config,
+			assets,
+			save,
+			pager: Pager::new(achievements.len()),
+			info: Text::new(format!("{}/{} unlocked", unlocked, achievements.len()), Font::default(), 8.0),
+			achievements,
+		};
+		if !scene.pager.is_empty(){
+			scene.select(0);
+		}
+		Ok(scene)
+	}
+
+	fn select(&mut self, index: usize){
+		let achievement = &self.achievements[index];
+		let unlocked = self.save.borrow().has_achievement(&achievement.id);
+		self.pager.set_page(&achievement.name, &wrap(&achievement.description), !unlocked);
+	}
+}
+
+// breaks the description between words
+fn wrap(text: &str) -> String{
+	let mut lines: Vec<String> = vec![];
+	for word in text.split_whitespace(){
+		match lines.last_mut(){
+			Some(line) if line.len() + 1 + word.len() <= LINE_LENGTH => {
+				line.push(' ');
+				line.push_str(word);
+			}
+			_ => lines.push(word.to_string()),
+		}
+	}
+	lines.join("\n")
+}
+
+impl Scene for AchievementsScene {
+	fn update(&mut self, ctx: &mut Context) -> tetra::Result<Transition> {
+		self.assets.borrow_mut().update();
+		if self.pager.is_empty(){
+			return Ok(Transition::Pop);
+		}
+		if let Some(index) = self.pager.update(ctx){
+			self.select(index);
+		}
+
+		if input::is_key_released(ctx, Key::Escape) || input::is_key_released(ctx, Key::Backspace){
+			Ok(Transition::Pop)
+		}else{
+			Ok(Transition::None)
+		}
+	}
+
+	fn draw(&mut self, ctx: &mut Context, _dt: f64) -> tetra::Result<Transition> {
+		graphics::clear(ctx, self.config.clear_color);
+		self.pager.draw(ctx);
+		graphics::draw(ctx, &self.info, GET_LEVEL_INFO_POSITION());
+		self.mouse.draw(ctx, DrawParams::default());
+
+		Ok(Transition::None)
+	}
+}
@@ -0,0 +1,313 @@
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::cell::{RefCell};
+
+use tetra::graphics::{self, DrawParams, Drawable, Rectangle};
+use tetra::graphics::text::{Text, Font};
+use tetra::input::{self, Key};
+use tetra::Context;
+
+use crate::scenes::manager::{Scene, Transition};
+use crate::scenes::game::GameScene;
+use crate::assets::Assets;
+use crate::models::config::Config;
+use crate::models::board::TileKind;
+use crate::models::level::{Level, MAX_SIZE, parse_level};
+use crate::models::save::SaveGame;
+use crate::models::solver::{solve_within, MAX_NODES};
+use crate::models::code;
+use crate::button::{Button,ButtonType};
+use crate::tile::{Tile, TileState};
+use crate::managers::tilemanager::get_positions;
+use crate::managers::levelmanager::ron_files;
+use crate::constants::*;
+use crate::utils::mouse::Mouse;
+use crate::utils::storage;
+
+pub struct EditorScene {
+	config: Rc<Config>,
+	assets: Rc<RefCell<Assets>>,
+	save: Rc<RefCell<SaveGame>>,
+	level: Level,
+	tiles: Vec<Tile>,
+	tool: EditorTool,
+	btn_test: Button,
+	tool_text: Text,
+	moves_text: Text,
+	status_text: Text,
+	playable: bool,
+	// the file of the first save, later saves in this session update it
+	saved_as: Option<PathBuf>,
+	mouse: Mouse,
+}
+
+impl EditorScene {
+	pub fn new(_ctx: &mut Context,config: Rc<Config>, assets: Rc<RefCell<Assets>>, save: Rc<RefCell<SaveGame>>, level: Level) -> tetra::Result<EditorScene> {
+		EditorScene {
+			btn_test: Button::new(Rc::clone(&assets), GET_POSITION_FUTURE_BUTTON(), GET_TOUCH_AREA_BUTTON(), ButtonType::Future)?,
+			mouse: Mouse::new(Rc::clone(&assets))?,
+			config,
+			assets,
+			save,
+			level,
+			tiles: vec![],
+			tool: EditorTool::Value,
+			tool_text: Text::new("", Font::default(), 8.0),
+			moves_text: Text::new("", Font::default(), 8.0),
+			status_text: Text::new("", Font::default(), 8.0),
+			playable: false,
+			saved_as: None,
+		}.init()
+	}
+
+	fn init(mut self) -> tetra::Result<Self>{
+		self.build_tiles()?;
+		self.tool_text.set_content(self.tool.name());
+		Ok(self)
+	}
+
+	// the same tiles the game draws, but every one of them can be clicked
+	fn build_tiles(&mut self) -> tetra::Result{
+		let (positions, scale) = get_positions(self.level.width, self.level.height);
+		let size = TILE_SIZE as f32 * scale;
+		let touch_area = Rectangle::new(0.0,0.0,size,size);
+		self.tiles.clear();
+		for position in positions{
+			self.tiles.push(Tile::new(Rc::clone(&self.assets), position, touch_area, 0)?.set_scale(scale).set_editing(true));
+		}
+		self.changed();
+		Ok(())
+	}
+
+	// shows the level as it starts, solving it waits for the player because it takes a moment
+	fn changed(&mut self){
+		for (i, tile) in self.tiles.iter_mut().enumerate(){
+			tile.number(self.level.values[i]);
+			tile.set_wall(self.level.kinds[i] == TileKind::Wall);
+			tile.set_enabled(self.level.states[i]);
+			tile.mark(self.level.flagged[i]);
+		}
+		self.moves_text.set_content(format!("{}/{} moves", self.level.moves, self.level.remaining_moves));
+		let errors = self.level.check();
+		self.playable = errors.is_empty();
+		if let Some(error) = errors.first(){
+			log::info!("level isn't playable: {}", error);
+			self.status_text.set_content("not playable");
+		}else{
+			self.status_text.set_content("space solves");
+		}
+	}
+
+	fn solve_level(&mut self){
+		let report = solve_within(&self.level, MAX_NODES);
+		self.status_text.set_content(match report.min_marks(){
+			Some(min) if report.complete => format!("min {} marks", min),
+			Some(min) => format!("wins with {} marks", min),
+			None if report.complete => "no solution".to_string(),
+			None => "too big to solve".to_string(),
+		});
+	}
+
+	fn edit_tile(&mut self, i: usize, state: TileState){
+		let level = &mut self.level;
+		let step = if state == TileState::Plus { 1 } else { level.max_value };
+		let is_wall = level.kinds[i] == TileKind::Wall;
+		match self.tool{
+			EditorTool::Value if !is_wall => {
+				level.values[i] = (level.values[i] + step) % (level.max_value + 1);
+				if level.values[i] == 0{
+					level.flagged[i] = TileState::Normal;
+				}
+			}
+			EditorTool::Enabled => level.states[i] = !level.states[i],
+			EditorTool::Flagged if !is_wall && level.values[i] > 0 => {
+				level.flagged[i] = if level.flagged[i] == state { TileState::Normal } else { state };
+			}
+			EditorTool::Wall => {
+				level.kinds[i] = if is_wall { TileKind::Normal } else { TileKind::Wall };
+				level.values[i] = 0;
+				level.flagged[i] = TileState::Normal;
+			}
+			_ => return,
+		}
+		// the par of the old board doesn't fit the new one
+		level.par = None;
+		self.changed();
+	}
+
+	fn resize(&mut self, width: usize, height: usize) -> tetra::Result{
+		self.level = self.level.resized(width, height);
+		self.level.par = None;
+		self.build_tiles()
+	}
+
+	fn save_level(&mut self){
+		let directory = match storage::editor_dir(){
+			Some(directory) => directory,
+			None => return,
+		};
+		let path = match &self.saved_as{
+			Some(path) => path.clone(),
+			None => {
+				// a new file after the highest number in use, the level keeps the number
+				let highest = ron_files(&directory).iter()
+					.filter_map(|(_, content)|parse_level(content).ok())
+					.map(|level|level.number)
+					.max()
+					.unwrap_or(0);
+				let number = match highest.checked_add(1){
+					Some(number) => number,
+					None => {
+						self.status_text.set_content("no numbers left");
+						return;
+					}
+				};
+				let path = directory.join(format!("level_{}.ron", number));
+				if path.exists(){
+					log::warn!("{} already exists, not overwriting it", path.display());
+					self.status_text.set_content("file exists");
+					return;
+				}
+				self.level.number = number;
+				path
+			}
+		};
+		let result = fs::create_dir_all(&directory).and_then(|_|fs::write(&path, self.level.to_ron()));
+		match result{
+			Ok(_) => {
+				log::info!("level saved to {}", path.display());
+				self.status_text.set_content(format!("saved as {}", self.level.number));
+				self.saved_as = Some(path);
+			}
+			Err(error) => {
+				log::warn!("can't save {}: {}", path.display(), error);
+				self.status_text.set_content("can't save");
+			}
+		}
+	}
+}
+
+// sizes go round from 1 to MAX_SIZE
+fn next_size(size: usize) -> usize{
+	if size >= MAX_SIZE { 1 } else { size + 1 }
+}
+
+impl Scene for EditorScene {
+	fn update(&mut self, ctx: &mut Context) -> tetra::Result<Transition> {
+		self.assets.borrow_mut().update();
+
+		// left click is plus, right click minus for every tool
+		let mut clicked = None;
+		for (i, tile) in self.tiles.iter_mut().enumerate(){
+			if let Some(state) = tile.update(ctx){
+				clicked = Some((i, state));
+			}
+		}
+		if let Some((i, state)) = clicked{
+			self.edit_tile(i, state);
+		}
+
+		let ctrl = input::is_key_down(ctx, Key::LCtrl) || input::is_key_down(ctx, Key::RCtrl);
+		if input::is_key_pressed(ctx, Key::Tab){
+			self.tool = self.tool.next();
+			self.tool_text.set_content(self.tool.name());
+		}
+		if ctrl && input::is_key_pressed(ctx, Key::S){
+			self.save_level();
+		}else if ctrl && input::is_key_pressed(ctx, Key::C) && self.playable{
+			let level_code = code::encode(&self.level);
+			match input::set_clipboard_text(ctx, &level_code){
+				Ok(_) => self.status_text.set_content("code copied"),
+				Err(error) => log::warn!("can't copy level code: {}", error),
+			}
+		}else if input::is_key_pressed(ctx, Key::Space) && self.playable{
+			self.solve_level();
+		}else if input::is_key_pressed(ctx, Key::W){
+			self.resize(next_size(self.level.width), self.level.height)?;
+		}else if input::is_key_pressed(ctx, Key::H){
+			self.resize(self.level.width, next_size(self.level.height))?;
+		}
+
+		// budget, remaining moves can't be more than moves
+		let (moves, remaining_moves) = (self.level.moves, self.level.remaining_moves);
+		if input::is_key_pressed(ctx, Key::Up){
+			self.level.moves = moves.saturating_add(1);
+		}else if input::is_key_pressed(ctx, Key::Down) && moves > 0{
+			self.level.moves -= 1;
+			self.level.remaining_moves = remaining_moves.min(moves - 1);
+		}else if input::is_key_pressed(ctx, Key::Right) && remaining_moves < moves{
+			self.level.remaining_moves += 1;
+		}else if input::is_key_pressed(ctx, Key::Left) && remaining_moves > 0{
+			self.level.remaining_moves -= 1;
+		}
+		if (moves, remaining_moves) != (self.level.moves, self.level.remaining_moves){
+			self.changed();
+		}
+
+		// test play
+		self.btn_test.update(ctx);
+		let test = self.btn_test.is_pressed() || input::is_key_released(ctx, Key::Return);
+		if self.btn_test.is_pressed(){
+			self.btn_test.get_pressed();
+		}
+		if test && self.playable{
+			let level = self.level.clone();
+			return Ok(Transition::Push(Box::new(GameScene::from_level(ctx, Rc::clone(&self.config), Rc::clone(&self.assets), Rc::clone(&self.save), level)?)));
+		}
+
+		if input::is_key_released(ctx, Key::Escape) || input::is_key_released(ctx, Key::Backspace){
+			Ok(Transition::Pop)
+		}else{
+			Ok(Transition::None)
+		}
+	}
+
+	fn draw(&mut self, ctx: &mut Context, _dt: f64) -> tetra::Result<Transition> {
+		graphics::clear(ctx, self.config.clear_color);
+
+		for tile in self.tiles.iter(){
+			tile.draw(ctx, DrawParams::default());
+		}
+
+		graphics::draw(ctx, &self.tool_text, GET_EDITOR_TOOL_POSITION());
+		graphics::draw(ctx, &self.moves_text, GET_EDITOR_MOVES_POSITION());
+		graphics::draw(ctx, &self.status_text, GET_HINT_TEXT_POSITION());
+		if self.playable{
+			graphics::draw(ctx, &self.btn_test, DrawParams::default());
+		}
+
+		self.mouse.draw(ctx, DrawParams::default());
+
+		Ok(Transition::None)
+	}
+}
+
+// what a click on a tile changes, tab switches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorTool {
+	Value,
+	Enabled,
+	Flagged,
+	Wall,
+}
+
+impl EditorTool {
+	fn next(self) -> EditorTool {
+		match self {
+			EditorTool::Value => EditorTool::Enabled,
+			EditorTool::Enabled => EditorTool::Flagged,
+			EditorTool::Flagged => EditorTool::Wall,
+			EditorTool::Wall => EditorTool::Value,
+		}
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			EditorTool::Value => "value",
+			EditorTool::Enabled => "enabled",
+			EditorTool::Flagged => "flagged",
+			EditorTool::Wall => "wall",
+		}
+	}
+}
@@ -1,7 +1,9 @@
 use std::rc::Rc;
 use std::cell::{RefCell};
+use std::time::Instant;
 
 use tetra::graphics::{self, DrawParams, Drawable, Color};
+use tetra::graphics::text::{Text, Font};
 use tetra::input::{self, Key};
 use tetra::{Context};
 use tetra::glm::Vec2;
@@ -10,78 +12,430 @@ use crate::scenes::manager::{Scene, Transition};
 use crate::assets::{Assets, AnimationName, TextureName};
 use crate::models::config::Config;
 use crate::button::{Button,ButtonType};
+use crate::toast::Toast;
 
 use crate::constants::*;
 use crate::utils::mouse::Mouse;
 use crate::managers::tilemanager::Tilemanager;
 use crate::managers::levelmanager::Levelmanager;
 use crate::models::crossover::Crossover;
+use crate::models::board::Outcome;
+use crate::models::solver::{self, Hint};
+use crate::models::replay::{Replay, ReplayAction};
+use crate::models::save::SaveGame;
+use crate::models::level::{Level, DEFAULT_SIZE};
+use crate::models::pack::Pack;
+use crate::models::code;
+use crate::models::rating::{self, MAX_STARS};
+use crate::models::time_attack::{TimeAttack, format_time};
+use crate::models::endless::Endless;
+use crate::models::stats::LevelStats;
+use crate::models::achievement::{self, Achievement, GameEvent, Win};
+use crate::utils::storage;
+use crate::tile::TileState;
 
 #[allow(dead_code)]
 pub struct GameScene {
 	config: Rc<Config>,
 	assets: Rc<RefCell<Assets>>,
+	save: Rc<RefCell<SaveGame>>,
 	state: GameState,
 	tilemanager: Tilemanager,
 	levelmanager: Levelmanager,
-	actions: u8,
 	btn_future: Button,
 	btn_back: Button,
+	btn_undo: Button,
+	btn_redo: Button,
+	btn_hint: Button,
 	mouse: Mouse,
 	clear_color: Color,
 	level_transition: Crossover,
+	// levels outside the installed packs don't touch the save game or write replays
+	campaign: bool,
+	future_outcome: Option<Outcome>,
+	// stars of the last win
+	stars: Option<u8>,
+	// the hint and the marks it was computed for
+	hint: Option<(Hint, Vec<TileState>)>,
+	hint_text: Text,
+	replay: Replay,
+	attempt_start: Instant,
+	time_attack: Option<TimeAttack>,
+	// the day of the daily puzzle being played
+	daily: Option<u64>,
+	endless: Option<Endless>,
+	achievements: Vec<Achievement>,
+	toast: Toast,
+	// marks the player placed before the last future, given ones left out
+	played_marks: Vec<TileState>,
+	// no loss on the current level in this scene, campaign levels ask their stats
+	first_try: bool,
+	clock_text: Text,
+	// time attack results, the splits in two columns
+	results_text: [Text; 3],
+	splits: Vec<String>,
+	splits_page: usize,
 }
 
 impl GameScene {
-	pub fn new(_ctx: &mut Context,config: Rc<Config>, assets: Rc<RefCell<Assets>>) -> tetra::Result<GameScene> {
+	pub fn new(_ctx: &mut Context,config: Rc<Config>, assets: Rc<RefCell<Assets>>, save: Rc<RefCell<SaveGame>>, packs: Rc<Vec<Pack>>, starting_level: u8) -> tetra::Result<GameScene> {
+		let mut levelmanager = Levelmanager::new(packs);
+		if !levelmanager.select_pack(&save.borrow().pack){
+			log::warn!("pack \"{}\" doesn't exist, playing the built-in levels", save.borrow().pack);
+		}
+		if !levelmanager.select_level(starting_level){
+			log::warn!("level {} doesn't exist, starting with the first one", starting_level);
+		}
+		GameScene::with_levels(config, assets, save, levelmanager, true)
+	}
+
+	// every level of the pack from the first one against the clock
+	pub fn time_attack(ctx: &mut Context,config: Rc<Config>, assets: Rc<RefCell<Assets>>, save: Rc<RefCell<SaveGame>>, packs: Rc<Vec<Pack>>) -> tetra::Result<GameScene> {
+		let mut scene = GameScene::new(ctx, config, assets, save, packs, 1)?;
+		scene.time_attack = Some(TimeAttack::new());
+		Ok(scene)
+	}
+
+	// plays a single level that isn't part of a pack, like one from the editor
+	pub fn from_level(_ctx: &mut Context,config: Rc<Config>, assets: Rc<RefCell<Assets>>, save: Rc<RefCell<SaveGame>>, level: Level) -> tetra::Result<GameScene> {
+		let name = level.name.clone();
+		let levelmanager = Levelmanager::from_pack(Pack::custom(&name, vec![level]));
+		GameScene::with_levels(config, assets, save, levelmanager, false)
+	}
 
+	// today's generated puzzle, a win counts for the streak
+	pub fn daily(ctx: &mut Context,config: Rc<Config>, assets: Rc<RefCell<Assets>>, save: Rc<RefCell<SaveGame>>, day: u64, level: Level) -> tetra::Result<GameScene> {
+		let mut scene = GameScene::from_level(ctx, config, assets, save, level)?;
+		scene.daily = Some(day);
+		Ok(scene)
+	}
+
+	// generated boards that get harder until the first loss
+	pub fn endless(ctx: &mut Context,config: Rc<Config>, assets: Rc<RefCell<Assets>>, save: Rc<RefCell<SaveGame>>) -> tetra::Result<GameScene> {
+		// a blank board outside the campaign until the first one is generated
+		let mut scene = GameScene::from_level(ctx, config, assets, save, Level::blank(1, DEFAULT_SIZE, DEFAULT_SIZE))?;
+		scene.start_endless()?;
+		Ok(scene)
+	}
+
+	fn with_levels(config: Rc<Config>, assets: Rc<RefCell<Assets>>, save: Rc<RefCell<SaveGame>>, levelmanager: Levelmanager, campaign: bool) -> tetra::Result<GameScene> {
 		Ok(GameScene {
 			clear_color: config.clear_color,
-			actions: 0,
 			state: GameState::Running,
-			tilemanager: Tilemanager::new(Rc::clone(&assets))?,
-			levelmanager: Levelmanager::new(1)?,
+			tilemanager: Tilemanager::new(Rc::clone(&assets), config.future_step_length)?,
 			btn_future: Button::new(Rc::clone(&assets), GET_POSITION_FUTURE_BUTTON(), GET_TOUCH_AREA_BUTTON(), ButtonType::Future)?,
 			btn_back: Button::new(Rc::clone(&assets), GET_POSITION_BACK_BUTTON(), GET_TOUCH_AREA_BUTTON(), ButtonType::Back)?,
+			btn_undo: Button::new(Rc::clone(&assets), GET_POSITION_UNDO_BUTTON(), GET_TOUCH_AREA_SMALL_BUTTON(), ButtonType::Undo)?,
+			btn_redo: Button::new(Rc::clone(&assets), GET_POSITION_REDO_BUTTON(), GET_TOUCH_AREA_SMALL_BUTTON(), ButtonType::Redo)?,
+			btn_hint: Button::new(Rc::clone(&assets), GET_POSITION_HINT_BUTTON(), GET_TOUCH_AREA_SMALL_BUTTON(), ButtonType::Hint)?,
 			mouse: Mouse::new(Rc::clone(&assets))?,
 			level_transition: Crossover::new(Rc::clone(&assets))?,
+			campaign,
+			future_outcome: None,
+			stars: None,
+			hint: None,
+			hint_text: Text::new("", Font::default(), 8.0),
+			replay: Replay::new(levelmanager.get_pack().name(), levelmanager.get_current_level()),
+			attempt_start: Instant::now(),
+			time_attack: None,
+			daily: None,
+			endless: None,
+			achievements: achievement::load_achievements(),
+			toast: Toast::new(Rc::clone(&assets)),
+			played_marks: vec![],
+			first_try: true,
+			clock_text: Text::new("", Font::default(), 8.0),
+			results_text: [Text::new("", Font::default(), 8.0), Text::new("", Font::default(), 8.0), Text::new("", Font::default(), 8.0)],
+			splits: vec![],
+			splits_page: 0,
+			levelmanager,
 			assets,
+			save,
 			config,
-		}.init())
+		}.init()?)
 	}
 	
-	fn init(mut self) -> Self{
-		self.init_level();
-		self
+	fn init(mut self) -> tetra::Result<Self>{
+		self.init_level()?;
+		Ok(self)
 	}
 	
-	fn init_level(&mut self){
+	fn init_level(&mut self) -> tetra::Result{
 		self.level_transition.play();
-		self.actions = self.levelmanager.get_current_level().moves;
-		self.tilemanager.init_level(self.levelmanager.get_current_level());
+		self.tilemanager.init_level(self.levelmanager.get_current_level())?;
+		self.future_outcome = None;
+		self.stars = None;
+		self.hint = None;
+		self.replay = Replay::new(self.levelmanager.get_pack().name(), self.levelmanager.get_current_level());
+		self.attempt_start = Instant::now();
+		self.record_stats(|stats|stats.attempts += 1);
 		self.state = GameState::Running;
-		self.clear_color = self.config.clear_color;
+		self.clear_color = self.levelmanager.get_pack().theme().background(self.config.clear_color);
 		self.btn_future.change_type_to(ButtonType::Future);
+		Ok(())
 	}
 	
-	pub fn next_level(&mut self){
-		if self.levelmanager.advance_next_level(){
-			self.init_level();
+	pub fn next_level(&mut self) -> tetra::Result{
+		self.first_try = true;
+		if self.endless.is_some(){
+			self.next_endless_level()?;
+		}else if self.levelmanager.advance_next_level(){
+			self.init_level()?;
 		}else{
 			self.state = GameState::End;
+			self.finish_time_attack();
+		}
+		Ok(())
+	}
+
+	// leaves the pack behind, nothing of the run goes into the campaign
+	fn start_endless(&mut self) -> tetra::Result{
+		self.campaign = false;
+		self.first_try = true;
+		self.endless = Some(Endless::new());
+		self.next_endless_level()
+	}
+
+	fn next_endless_level(&mut self) -> tetra::Result{
+		let level = match self.endless.as_ref().and_then(|endless|endless.next_level()){
+			Some(level) => level,
+			None => {
+				log::warn!("no board left to generate, the endless run is over");
+				self.finish_endless();
+				return Ok(());
+			}
+		};
+		let name = level.name.clone();
+		self.levelmanager = Levelmanager::from_pack(Pack::custom(&name, vec![level]));
+		self.init_level()
+	}
+
+	fn finish_endless(&mut self){
+		let cleared = match &self.endless{
+			Some(endless) => endless.cleared(),
+			None => return,
+		};
+		self.state = GameState::End;
+		let mut save = self.save.borrow_mut();
+		let new_best = save.add_endless(cleared);
+		save.save();
+		self.results_text[0].set_content(format!("cleared {}\n{} {}",
+			cleared,
+			if new_best { "new best" } else { "best" },
+			save.best_endless));
+		drop(save);
+		self.achieve(GameEvent::EndlessOver(cleared));
+	}
+
+	fn finish_time_attack(&mut self){
+		let run = match &self.time_attack{
+			Some(time_attack) => time_attack.finish(),
+			None => return,
+		};
+		let mut save = self.save.borrow_mut();
+		let place = save.add_time_attack(run.clone());
+		save.save();
+		let best = save.best_times().first().map_or(run.total, |r|r.total);
+		self.results_text[0].set_content(format!("time {}\nbest {}{}",
+			format_time(run.total),
+			format_time(best),
+			place.map_or(String::new(), |p|format!("\nplace {}", p + 1))));
+		self.splits = run.splits.iter().enumerate()
+			.map(|(i, &split)|format!("{} {}", i + 1, format_time(split)))
+			.collect();
+		drop(save);
+		self.show_splits(0);
+		self.achieve(GameEvent::TimeAttackFinished(run.total));
+	}
+
+	fn split_pages(&self) -> usize{
+		let per_page = SPLITS_PER_COLUMN * 2;
+		self.splits.len().div_ceil(per_page).max(1)
+	}
+
+	// two columns of splits at a time, the pages go round
+	fn show_splits(&mut self, page: usize){
+		self.splits_page = page % self.split_pages();
+		let mut columns = self.splits.chunks(SPLITS_PER_COLUMN).skip(self.splits_page * 2);
+		for text in self.results_text[1..].iter_mut(){
+			text.set_content(columns.next().map_or(String::new(), |column|column.join("\n")));
+		}
+		if self.split_pages() > 1{
+			self.clock_text.set_content(format!("{}/{}", self.splits_page + 1, self.split_pages()));
+		}
+	}
+
+	// only the end of a pack leads into endless, not a daily or a single level
+	fn offers_endless(&self) -> bool{
+		self.campaign && self.daily.is_none() && self.time_attack.is_none()
+	}
+
+	// levels from the editor or a code can be made up to earn anything
+	fn earns_achievements(&self) -> bool{
+		self.campaign || self.daily.is_some() || self.endless.is_some()
+	}
+
+	fn achieve(&mut self, event: GameEvent){
+		if !self.earns_achievements(){
+			return;
+		}
+		let mut save = self.save.borrow_mut();
+		let unlocked = achievement::unlock(&self.achievements, &mut save, &event);
+		if unlocked.is_empty(){
+			return;
+		}
+		save.save();
+		for achievement in unlocked{
+			log::info!("achievement unlocked: {}", achievement.name);
+			self.toast.show(achievement.name.clone());
 		}
 	}
 	
+	fn show_hint(&mut self){
+		// asking again for the hint on screen is free
+		if self.current_hint().is_some(){
+			return;
+		}
+		let board = self.tilemanager.get_board();
+		let hint = solver::hint(board, &self.levelmanager.get_current_level().flagged);
+		self.hint_text.set_content(match hint{
+			Hint::Impossible => "no win from here",
+			Hint::Ready => "press future",
+			Hint::Unknown => "too big for a hint",
+			Hint::Mark(_, _) => "",
+		});
+		self.hint = Some((hint, board.marks.clone()));
+		self.levelmanager.add_hint();
+		self.record_stats(|stats|stats.hints += 1);
+	}
+
+	// only levels of a pack keep stats, they are written with the next outcome
+	fn record_stats<F: FnOnce(&mut LevelStats)>(&mut self, change: F){
+		if !self.campaign{
+			return;
+		}
+		let number = self.levelmanager.get_current_level().number;
+		change(&mut self.save.borrow_mut().progress_mut(number).stats);
+	}
+
+	// lost is None when the level is left before the future was played
+	fn end_attempt(&mut self, lost: Option<bool>){
+		let milliseconds = self.attempt_start.elapsed().as_millis() as u64;
+		let level = self.levelmanager.get_current_level();
+		let marks = if lost.is_some() { u32::from(level.moves.saturating_sub(self.tilemanager.actions())) } else { 0 };
+		self.record_stats(|stats|{
+			stats.milliseconds += milliseconds;
+			stats.marks += marks;
+			if lost == Some(true){
+				stats.losses += 1;
+			}
+		});
+		if self.campaign{
+			self.save.borrow().save();
+		}
+	}
+
+	// a hint is only shown until the marks change
+	fn current_hint(&self) -> Option<Hint>{
+		match &self.hint{
+			Some((hint, marks)) if *marks == self.tilemanager.get_board().marks => Some(*hint),
+			_ => None,
+		}
+	}
+
+	fn record(&mut self, action: ReplayAction){
+		let time = self.attempt_start.elapsed().as_millis() as u64;
+		self.replay.push(time, action);
+	}
+
+	fn save_replay(&self){
+		if let Some(directory) = storage::replay_dir(){
+			match self.replay.save(&directory){
+				Ok(path) => log::info!("replay saved to {}", path.display()),
+				Err(error) => log::warn!("can't save replay: {}", error),
+			}
+		}
+	}
+
 	fn go_future(&mut self){
 		self.state = GameState::Future;
-		let number_of_visible_tiles = self.tilemanager.go_future();
-		if number_of_visible_tiles == 0{
-			self.state = GameState::Win;
-			self.clear_color = GET_FUTURE_COLOR();
-			self.actions = 0;
-			self.btn_future.change_type_to(ButtonType::Next);
-		}else{
-			self.state = GameState::Lost;
+		self.hint = None;
+		self.record(ReplayAction::Future);
+		let flagged = &self.levelmanager.get_current_level().flagged;
+		self.played_marks = self.tilemanager.get_board().marks.iter().zip(flagged.iter())
+			.filter(|&(&mark, &given)|mark != TileState::Normal && given == TileState::Normal)
+			.map(|(&mark, _)|mark)
+			.collect();
+		if self.campaign{
+			self.save_replay();
+		}
+		self.future_outcome = Some(self.tilemanager.go_future());
+	}
+
+	// the losses of a campaign level are kept over every session
+	fn is_first_try(&self) -> bool{
+		if !self.campaign{
+			return self.first_try;
+		}
+		let number = self.levelmanager.get_current_level().number;
+		self.save.borrow().progress(number).is_none_or(|p|p.stats.losses == 0)
+	}
+
+	fn rate_win(&mut self){
+		let level = self.levelmanager.get_current_level();
+		let moves_used = level.moves.saturating_sub(self.tilemanager.actions());
+		let stars = rating::stars(moves_used, rating::par(level), level.moves);
+		self.stars = Some(stars);
+		let first_try = self.is_first_try();
+		if self.campaign{
+			let mut save = self.save.borrow_mut();
+			save.complete_level(level.number, moves_used, stars, self.levelmanager.get_next_level_number());
+			save.save();
+		}
+		let win = Win{
+			level: if self.campaign { Some(level.number) } else { None },
+			marks: self.played_marks.clone(),
+			spare_moves: self.tilemanager.actions(),
+			first_try,
+			hints: self.levelmanager.get_hints_used(),
+			stars,
+		};
+		self.achieve(GameEvent::Won(win));
+		if let Some(day) = self.daily{
+			let mut save = self.save.borrow_mut();
+			save.daily.solve(day);
+			save.save();
+			drop(save);
+			self.achieve(GameEvent::DailySolved);
+		}
+	}
+
+	// called once the future has been played back
+	fn finish_future(&mut self){
+		let outcome = match self.future_outcome.take(){
+			Some(outcome) => outcome,
+			None => return,
+		};
+		self.end_attempt(Some(outcome != Outcome::Cleared));
+		match outcome{
+			Outcome::Cleared => {
+				self.state = GameState::Win;
+				self.rate_win();
+				if let Some(time_attack) = self.time_attack.as_mut(){
+					time_attack.split();
+				}
+				if let Some(endless) = self.endless.as_mut(){
+					endless.clear();
+				}
+				self.clear_color = self.levelmanager.get_pack().theme().future(GET_FUTURE_COLOR());
+				self.tilemanager.clear_actions();
+				self.btn_future.change_type_to(ButtonType::Next);
+			}
+			Outcome::Remaining(_) => self.state = GameState::Lost,
+			Outcome::Capped => {
+				log::warn!("level {} still dissolving after the round limit", self.levelmanager.get_current_level().number);
+				self.state = GameState::Lost;
+			}
 		}
 	}
 }
@@ -92,14 +446,64 @@ impl Scene for GameScene {
 		self.assets.borrow_mut().update();
 
 		// update tiles
-		self.tilemanager.update(ctx, &mut self.actions);
+		if let Some((index, state)) = self.tilemanager.update(ctx){
+			self.record(if state == TileState::Normal { ReplayAction::Unmark(index) } else { ReplayAction::Mark(index, state) });
+		}
+
+		// undo and redo marks while planning
+		if self.state == GameState::Running{
+			let ctrl = input::is_key_down(ctx, Key::LCtrl) || input::is_key_down(ctx, Key::RCtrl);
+			self.btn_undo.update(ctx);
+			self.btn_redo.update(ctx);
+			let undo = self.btn_undo.get_pressed() || (ctrl && input::is_key_pressed(ctx, Key::Z));
+			let redo = self.btn_redo.get_pressed() || (ctrl && input::is_key_pressed(ctx, Key::Y));
+			if undo && self.tilemanager.undo(){
+				self.record(ReplayAction::Undo);
+			}else if redo && self.tilemanager.redo(){
+				self.record(ReplayAction::Redo);
+			}
+
+			// share the level as a code
+			if ctrl && input::is_key_pressed(ctx, Key::C){
+				let level_code = code::encode(self.levelmanager.get_current_level());
+				if let Err(error) = input::set_clipboard_text(ctx, &level_code){
+					log::warn!("can't copy level code: {}", error);
+				}
+			}
+
+			// hint button
+			self.btn_hint.update(ctx);
+			if self.btn_hint.is_pressed(){
+				self.btn_hint.get_pressed();
+				self.show_hint();
+			}else if input::is_key_pressed(ctx, Key::H){
+				self.show_hint();
+			}
+		}
 		
-		// back button
-		if self.state == GameState::Lost{
+		// future playback, space or return skips it
+		if self.state == GameState::Future{
+			if input::is_key_pressed(ctx, Key::Space) || input::is_key_pressed(ctx, Key::Return){
+				self.tilemanager.skip();
+			}
+			if !self.tilemanager.is_playing(){
+				self.finish_future();
+			}
+		}else if self.state == GameState::Lost{
+			// back button
 			self.btn_back.update(ctx);
 			if self.btn_back.is_pressed(){
 				self.btn_back.get_pressed();
-				self.init_level();
+				if let Some(time_attack) = self.time_attack.as_mut(){
+					time_attack.penalty();
+				}
+				// the first loss ends an endless run
+				if self.endless.is_some(){
+					self.finish_endless();
+				}else{
+					self.first_try = false;
+					self.init_level()?;
+				}
 			}
 		}else{
 			// future button
@@ -107,7 +511,14 @@ impl Scene for GameScene {
 			if self.btn_future.is_pressed(){
 				self.btn_future.get_pressed();
 				if self.state == GameState::Win{
-					self.next_level()
+					self.next_level()?;
+				}else if self.state == GameState::End{
+					// after the last level the boards keep coming
+					if self.time_attack.is_some(){
+						self.show_splits(self.splits_page + 1);
+					}else if self.offers_endless(){
+						self.start_endless()?;
+					}
 				}else{
 					self.go_future();
 				}
@@ -115,9 +526,18 @@ impl Scene for GameScene {
 		}
 
 		self.level_transition.update();
+		self.toast.update();
+		if let Some(time_attack) = self.time_attack.as_ref().filter(|_|self.state != GameState::End){
+			self.clock_text.set_content(format_time(time_attack.elapsed()));
+		}else if let Some(endless) = &self.endless{
+			self.clock_text.set_content(endless.cleared().to_string());
+		}
 
 		// check keys
 		if input::is_key_released(ctx, Key::Backspace){
+			if self.state == GameState::Running{
+				self.end_attempt(None);
+			}
 			Ok(Transition::Pop)
 		}else{
 			Ok(Transition::None)
@@ -127,24 +547,81 @@ impl Scene for GameScene {
 	fn draw(&mut self, ctx: &mut Context, _dt: f64) -> tetra::Result<Transition> {
 		graphics::clear(ctx, self.clear_color);
 
+		// the endless score replaces the board
+		if self.state == GameState::End && self.endless.is_some(){
+			graphics::draw(ctx, &self.results_text[0], GET_RESULTS_POSITION());
+			self.toast.draw(ctx);
+			self.mouse.draw(ctx, DrawParams::default());
+			return Ok(Transition::None);
+		}
+
+		// the time attack results replace the empty board
+		if self.state == GameState::End && self.time_attack.is_some(){
+			graphics::draw(ctx, &self.results_text[0], GET_RESULTS_POSITION());
+			graphics::draw(ctx, &self.results_text[1], GET_SPLITS_POSITION());
+			graphics::draw(ctx, &self.results_text[2], GET_SPLITS_POSITION() + Vec2::new(SPLITS_COLUMN_WIDTH, 0.0));
+			if self.split_pages() > 1{
+				graphics::draw(ctx, &self.clock_text, GET_CLOCK_POSITION());
+				graphics::draw(ctx, &self.btn_future, DrawParams::default());
+			}
+			self.toast.draw(ctx);
+			self.mouse.draw(ctx, DrawParams::default());
+			return Ok(Transition::None);
+		}
+
 		//draw tiles
 		self.tilemanager.draw(ctx);
 
+		if self.time_attack.is_some() || self.endless.is_some(){
+			graphics::draw(ctx, &self.clock_text, GET_CLOCK_POSITION());
+		}
+
+		//draw undo and hint buttons
+		if self.state == GameState::Running{
+			if self.tilemanager.can_undo(){
+				graphics::draw(ctx, &self.btn_undo, DrawParams::default());
+			}
+			if self.tilemanager.can_redo(){
+				graphics::draw(ctx, &self.btn_redo, DrawParams::default());
+			}
+			graphics::draw(ctx, &self.btn_hint, DrawParams::default());
+			match self.current_hint(){
+				Some(Hint::Mark(index, state)) => self.tilemanager.draw_hint(ctx, index, state),
+				Some(_) => graphics::draw(ctx, &self.hint_text, GET_HINT_TEXT_POSITION()),
+				None => (),
+			}
+		}
+
 		//draw actions symbol
-		for i in 0..self.actions{
+		for i in 0..self.tilemanager.actions(){
 			graphics::draw(ctx,self.assets.borrow().get_animation(&AnimationName::Action),
 				Vec2::new(f32::from(i*10 + X_POSITION_MOVES_SYMBOLE), f32::from(Y_POSITION_MOVES_SYMBOLE)));
 		}
 
+		//draw stars where the actions were, missing ones faded
+		if let Some(stars) = self.stars.filter(|_|self.state == GameState::Win){
+			for i in 0..MAX_STARS{
+				let color = if i < stars { Color::rgb(1.0,1.0,1.0) } else { Color::rgba(1.0,1.0,1.0,0.3) };
+				graphics::draw(ctx, self.assets.borrow().get_texture(&TextureName::Star), DrawParams::new()
+					.position(Vec2::new(f32::from(i*10 + X_POSITION_MOVES_SYMBOLE), f32::from(Y_POSITION_MOVES_SYMBOLE)))
+					.color(color)
+					);
+			}
+		}
+
 		// draw future, next or back buttons
 		if self.state == GameState::Lost && self.tilemanager.is_ready(){
 			graphics::draw(ctx, &self.btn_back, DrawParams::default());
 		}else if self.state == GameState::End && self.tilemanager.is_ready() {
 			graphics::draw(ctx, self.assets.borrow().get_texture(&TextureName::Thx), GET_THX_POSITION());
-		}else if self.actions <= self.levelmanager.get_current_level().remaining_moves && self.tilemanager.is_ready() || self.state == GameState::Win && self.tilemanager.is_ready() {
+			if self.offers_endless(){
+				graphics::draw(ctx, &self.btn_future, DrawParams::default());
+			}
+		}else if self.tilemanager.get_board().can_go_future() && self.tilemanager.is_ready() || self.state == GameState::Win && self.tilemanager.is_ready() {
 			graphics::draw(ctx, &self.btn_future, DrawParams::default());
 		}
 		
+		self.toast.draw(ctx);
 		self.mouse.draw(ctx, DrawParams::default());
 
 		self.level_transition.draw(ctx);
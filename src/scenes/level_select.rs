@@ -0,0 +1,209 @@
+use std::rc::Rc;
+use std::cell::{RefCell};
+
+use tetra::{Context, input, glm};
+use tetra::input::{Key, MouseButton};
+use tetra::graphics::{self, Vec2, Color, Drawable, DrawParams};
+use tetra::graphics::text::{Text, Font};
+
+use crate::assets::{Assets, TextureName, AnimationName};
+use crate::scenes::manager::{Scene, Transition};
+use crate::scenes::game::GameScene;
+use crate::scenes::editor::EditorScene;
+use crate::managers::levelmanager::Levelmanager;
+use crate::models::config::Config;
+use crate::models::level::Level;
+use crate::models::pack::Pack;
+use crate::models::save::SaveGame;
+use crate::models::rating::{self, MAX_STARS};
+use crate::constants::*;
+use crate::utils::mouse::Mouse;
+use crate::utils::preview::draw_preview;
+
+const COLUMNS: usize = 4;
+const VISIBLE_ROWS: usize = 3;
+const CELL_SIZE: f32 = 22.0;
+const PREVIEW_SIZE: f32 = 18.0;
+
+pub struct LevelSelectScene {
+	config: Rc<Config>,
+	assets: Rc<RefCell<Assets>>,
+	save: Rc<RefCell<SaveGame>>,
+	packs: Rc<Vec<Pack>>,
+	levels: Vec<Level>,
+	selected: usize,
+	first_row: usize,
+	// the save may have changed while a level was played
+	refresh: bool,
+	title: Text,
+	info: Text,
+	mouse: Mouse,
+}
+
+impl LevelSelectScene {
+	pub fn new(_ctx: &mut Context,config: Rc<Config>, assets: Rc<RefCell<Assets>>, save: Rc<RefCell<SaveGame>>, packs: Rc<Vec<Pack>>) -> tetra::Result<LevelSelectScene> {
+		let mut levelmanager = Levelmanager::new(Rc::clone(&packs));
+		levelmanager.select_pack(&save.borrow().pack);
+		let levels = levelmanager.get_levels().to_vec();
+		Ok(LevelSelectScene {
+			mouse: Mouse::new(Rc::clone(&assets))?,
+			config,
+			assets,
+			save,
+			packs,
+			levels,
+			selected: 0,
+			first_row: 0,
+			refresh: false,
+			title: Text::new("", Font::default(), 8.0),
+			info: Text::new("", Font::default(), 8.0),
+		}.init())
+	}
+
+	fn init(mut self) -> Self{
+		// start at the level continue would pick
+		let highest = self.save.borrow().highest_unlocked();
+		self.selected = self.levels.iter().position(|l|l.number == highest).unwrap_or(0);
+		self.select(self.selected);
+		self
+	}
+
+	fn cell_position(&self, index: usize) -> Vec2{
+		let row = index / COLUMNS - self.first_row;
+		let column = index % COLUMNS;
+		GET_LEVEL_GRID_POSITION() + Vec2::new(column as f32 * CELL_SIZE, row as f32 * CELL_SIZE)
+	}
+
+	fn cell_at(&self, position: Vec2) -> Option<usize>{
+		let relative = position - GET_LEVEL_GRID_POSITION();
+		if relative.x < 0.0 || relative.y < 0.0{
+			return None;
+		}
+		let column = (relative.x / CELL_SIZE) as usize;
+		let row = (relative.y / CELL_SIZE) as usize;
+		if column >= COLUMNS || row >= VISIBLE_ROWS{
+			return None;
+		}
+		Some((self.first_row + row) * COLUMNS + column).filter(|&index|index < self.levels.len())
+	}
+
+	fn select(&mut self, index: usize){
+		self.selected = index;
+		let row = index / COLUMNS;
+		if row < self.first_row{
+			self.first_row = row;
+		}else if row >= self.first_row + VISIBLE_ROWS{
+			self.first_row = row + 1 - VISIBLE_ROWS;
+		}
+		// the solver runs the first time a level is selected, not at startup
+		let level = &mut self.levels[index];
+		if level.par.is_none(){
+			level.par = Some(rating::par(level));
+		}
+		let level = &self.levels[index];
+		let save = self.save.borrow();
+		self.title.set_content(format!("{} {}", level.number, level.name));
+		self.info.set_content(if !save.is_unlocked(level.number){
+			"locked".to_string()
+		}else{
+			let par = level.par.unwrap_or(level.moves);
+			save.progress(level.number).and_then(|p|p.best_moves).map_or(format!("par {}", par), |best|format!("best {} par {}", best, par))
+		});
+	}
+
+	fn is_unlocked(&self, index: usize) -> bool{
+		self.save.borrow().is_unlocked(self.levels[index].number)
+	}
+}
+
+impl Scene for LevelSelectScene {
+	fn update(&mut self, ctx: &mut Context) -> tetra::Result<Transition> {
+		self.assets.borrow_mut().update();
+		if self.levels.is_empty(){
+			return Ok(Transition::Pop);
+		}
+
+		if self.refresh{
+			self.refresh = false;
+			self.select(self.selected);
+		}
+
+		// move the selection with the arrow keys or the mouse
+		let last = self.levels.len() - 1;
+		let mut selected = self.selected;
+		if input::is_key_pressed(ctx, Key::Left) && selected > 0{
+			selected -= 1;
+		}else if input::is_key_pressed(ctx, Key::Right){
+			selected = (selected + 1).min(last);
+		}else if input::is_key_pressed(ctx, Key::Up) && selected >= COLUMNS{
+			selected -= COLUMNS;
+		}else if input::is_key_pressed(ctx, Key::Down){
+			selected = (selected + COLUMNS).min(last);
+		}
+		let hovered = self.cell_at(glm::round(&input::get_mouse_position(ctx)));
+		if let Some(index) = hovered{
+			selected = index;
+		}
+		if selected != self.selected{
+			self.select(selected);
+		}
+
+		let chosen = (hovered.is_some() && input::is_mouse_button_released(ctx, MouseButton::Left))
+			|| input::is_key_released(ctx, Key::Return) || input::is_key_released(ctx, Key::Space);
+		if chosen && self.is_unlocked(self.selected){
+			let number = self.levels[self.selected].number;
+			self.refresh = true;
+			Ok(Transition::Push(Box::new(GameScene::new(ctx, Rc::clone(&self.config), Rc::clone(&self.assets), Rc::clone(&self.save), Rc::clone(&self.packs), number)?)))
+		}else if input::is_key_released(ctx, Key::E){
+			// opens a copy in the editor, locked or not
+			let level = self.levels[self.selected].clone();
+			Ok(Transition::Push(Box::new(EditorScene::new(ctx, Rc::clone(&self.config), Rc::clone(&self.assets), Rc::clone(&self.save), level)?)))
+		}else if input::is_key_released(ctx, Key::Escape) || input::is_key_released(ctx, Key::Backspace){
+			Ok(Transition::Pop)
+		}else{
+			Ok(Transition::None)
+		}
+	}
+
+	fn draw(&mut self, ctx: &mut Context, _dt: f64) -> tetra::Result<Transition> {
+		graphics::clear(ctx, self.config.clear_color);
+		graphics::draw(ctx, &self.title, GET_LEVEL_TITLE_POSITION());
+
+		let assets = self.assets.borrow();
+		let first = self.first_row * COLUMNS;
+		let last = (first + VISIBLE_ROWS * COLUMNS).min(self.levels.len());
+		let highlight_scale = CELL_SIZE / TILE_SIZE as f32;
+		for index in first..last{
+			let position = self.cell_position(index);
+			let level = &self.levels[index];
+			if index == self.selected{
+				graphics::draw(ctx, assets.get_texture(&TextureName::Highlight), DrawParams::new()
+					.position(position)
+					.scale(Vec2::new(highlight_scale, highlight_scale))
+					);
+			}
+			// locked levels are faded out, completed ones get a mark in the corner
+			let save = self.save.borrow();
+			let color = if save.is_unlocked(level.number) { Color::rgb(1.0, 1.0, 1.0) } else { Color::rgba(1.0, 1.0, 1.0, 0.3) };
+			let margin = (CELL_SIZE - PREVIEW_SIZE) / 2.0;
+			draw_preview(ctx, &assets, level, position + Vec2::new(margin, margin), PREVIEW_SIZE, color);
+			if save.is_completed(level.number){
+				graphics::draw(ctx, assets.get_animation(&AnimationName::Action), position + Vec2::new(CELL_SIZE - 8.0, CELL_SIZE - 8.0));
+			}
+		}
+
+		graphics::draw(ctx, &self.info, GET_LEVEL_INFO_POSITION());
+		if let Some(progress) = self.save.borrow().progress(self.levels[self.selected].number){
+			for i in 0..MAX_STARS{
+				let color = if i < progress.stars { Color::rgb(1.0, 1.0, 1.0) } else { Color::rgba(1.0, 1.0, 1.0, 0.3) };
+				graphics::draw(ctx, assets.get_texture(&TextureName::Star), DrawParams::new()
+					.position(GET_LEVEL_STARS_POSITION() + Vec2::new(f32::from(i) * 10.0, 0.0))
+					.color(color)
+					);
+			}
+		}
+		self.mouse.draw(ctx, DrawParams::default());
+
+		Ok(Transition::None)
+	}
+}
@@ -2,59 +2,245 @@ use std::rc::Rc;
 use std::cell::{RefCell};
 
 use tetra::{Context, input, audio};
-use tetra::input::{Key, MouseButton};
-use tetra::graphics::{self,Vec2};
+use tetra::input::{Key};
+use tetra::graphics::{self, Vec2, Drawable, DrawParams};
+use tetra::graphics::text::{Text, Font};
 use tetra::audio::{Sound, SoundInstance};
 
 use crate::assets::{Assets, TextureName};
 use crate::scenes::manager::{Scene, Transition};
 use crate::scenes::game::GameScene;
+use crate::scenes::level_select::LevelSelectScene;
+use crate::scenes::editor::EditorScene;
+use crate::scenes::stats::StatsScene;
+use crate::scenes::achievements::AchievementsScene;
+use crate::managers::levelmanager::{self, Levelmanager};
 use crate::models::config::Config;
+use crate::models::pack::DEFAULT_PACK;
+use crate::models::level::{Level, DEFAULT_SIZE};
+use crate::models::save::SaveGame;
+use crate::models::code;
+use crate::models::daily;
+use crate::menu::Menu;
 use crate::constants::*;
+use crate::utils::mouse::Mouse;
 use crate::utils::tween::TweenPosition;
+use crate::utils::timer::Timer;
+
+const MAX_PACK_LABEL: usize = 14;
 
 #[allow(dead_code)]
 pub struct MenuScene {
 	config: Rc<Config>,
 	assets: Rc<RefCell<Assets>>,
+	save: Rc<RefCell<SaveGame>>,
+	levelmanager: Levelmanager,
 	background_music_instance: SoundInstance,
 	tween_logo: TweenPosition,
+	menu: Menu<MenuEntry>,
+	mouse: Mouse,
+	// feedback for the code entries, hidden when the timer runs out
+	message: Text,
+	message_timer: Timer,
 }
 
 impl MenuScene {
 	pub fn new(ctx: &mut Context,config: Rc<Config>, assets: Rc<RefCell<Assets>>) -> tetra::Result<MenuScene> {
 		audio::set_master_volume(ctx, config.master_volume);
-		let background_music = Sound::from_file_data(include_bytes!("../../assets/music/track.mp3"));
-		let background_music_instance = background_music.spawn(ctx)?;
-		background_music_instance.set_repeating(true);
-		background_music_instance.play();
-		background_music_instance.set_volume(0.2);
+		let mut save = SaveGame::load();
+		let mut levelmanager = Levelmanager::new(Rc::new(levelmanager::load_packs()));
+		if !levelmanager.select_pack(&save.pack){
+			save.pack = DEFAULT_PACK.to_string();
+		}
+		let background_music_instance = pack_music(ctx, &levelmanager)?;
+		let menu = Menu::new(GET_MENU_POSITION(), menu_entries(&save, &levelmanager));
 		Ok(MenuScene {
+			mouse: Mouse::new(Rc::clone(&assets))?,
 			config,
 			assets,
+			save: Rc::new(RefCell::new(save)),
+			levelmanager,
 			background_music_instance,
 			tween_logo: TweenPosition::new(GET_LOGO_POSITION(), 0.1, 6, Vec2::new(0.0,1.0)),
+			menu,
+			message: Text::new("", Font::default(), 8.0),
+			message_timer: Timer::new(2),
 		})
 	}
+
+	fn show_message(&mut self, message: &str){
+		self.message.set_content(message);
+		self.message_timer.restart();
+	}
+
+	fn import_code(&mut self, ctx: &mut Context) -> tetra::Result<Transition>{
+		let level = input::get_clipboard_text(ctx)
+			.map_err(|e|e.to_string())
+			.and_then(|text|code::decode(&text));
+		match level{
+			Ok(level) => Ok(Transition::Push(Box::new(GameScene::from_level(ctx, Rc::clone(&self.config), Rc::clone(&self.assets), Rc::clone(&self.save), level)?))),
+			Err(error) => {
+				log::warn!("can't import level code: {}", error);
+				self.show_message("no level code");
+				Ok(Transition::None)
+			}
+		}
+	}
+
+	// the level continue would start
+	fn export_code(&mut self, ctx: &mut Context){
+		let number = self.save.borrow().highest_unlocked();
+		let level = self.levelmanager.get_levels().iter().find(|l|l.number == number).unwrap_or(&self.levelmanager.get_levels()[0]);
+		let level_code = code::encode(level);
+		match input::set_clipboard_text(ctx, &level_code){
+			Ok(_) => self.show_message("code copied"),
+			Err(error) => {
+				log::warn!("can't copy level code: {}", error);
+				self.show_message("can't copy");
+			}
+		}
+	}
+
+	// the save changes while playing, so continue can show up after the first win
+	fn refresh_menu(&mut self){
+		let entries = menu_entries(&self.save.borrow(), &self.levelmanager);
+		if !self.menu.has_entries(&entries){
+			self.menu.set_entries(entries);
+		}
+	}
+
+	// the same puzzle for everybody on the same day
+	fn start_daily(&mut self, ctx: &mut Context) -> tetra::Result<Transition>{
+		let day = daily::today();
+		match daily::daily_level(day){
+			Some(level) => Ok(Transition::Push(Box::new(GameScene::daily(ctx, Rc::clone(&self.config), Rc::clone(&self.assets), Rc::clone(&self.save), day, level)?))),
+			None => {
+				log::warn!("no daily puzzle for {}", daily::date(day));
+				self.show_message("no daily puzzle");
+				Ok(Transition::None)
+			}
+		}
+	}
+
+	fn start_game(&self, ctx: &mut Context, level: u8) -> tetra::Result<Transition>{
+		Ok(Transition::Push(Box::new(GameScene::new(ctx, Rc::clone(&self.config), Rc::clone(&self.assets), Rc::clone(&self.save), self.levelmanager.get_shared_packs(), level)?)))
+	}
+
+	fn next_pack(&mut self, ctx: &mut Context) -> tetra::Result{
+		let packs = self.levelmanager.get_packs();
+		let current = packs.iter().position(|p|p.name() == self.levelmanager.get_pack().name()).unwrap_or(0);
+		let name = packs[(current + 1) % packs.len()].name().to_string();
+		self.levelmanager.select_pack(&name);
+		let mut save = self.save.borrow_mut();
+		save.pack = name;
+		save.save();
+		self.background_music_instance.stop();
+		self.background_music_instance = pack_music(ctx, &self.levelmanager)?;
+		Ok(())
+	}
+}
+
+// packs can bring their own music
+fn pack_music(ctx: &mut Context, levelmanager: &Levelmanager) -> tetra::Result<SoundInstance>{
+	let background_music = match &levelmanager.get_pack().music{
+		Some(music) => Sound::from_file_data(music),
+		None => Sound::from_file_data(include_bytes!("../../assets/music/track.mp3")),
+	};
+	let background_music_instance = background_music.spawn(ctx)?;
+	background_music_instance.set_repeating(true);
+	background_music_instance.play();
+	background_music_instance.set_volume(0.2);
+	Ok(background_music_instance)
+}
+
+fn menu_entries(save: &SaveGame, levelmanager: &Levelmanager) -> Vec<(MenuEntry, String)>{
+	let mut entries = vec![];
+	if save.has_progress(){
+		entries.push((MenuEntry::Continue, "continue".to_string()));
+	}
+	entries.push((MenuEntry::NewGame, "new game".to_string()));
+	entries.push((MenuEntry::LevelSelect, "levels".to_string()));
+	entries.push((MenuEntry::TimeAttack, "time attack".to_string()));
+	let today = daily::today();
+	let streak = save.daily.current_streak(today);
+	entries.push((MenuEntry::Endless, if save.best_endless > 0 { format!("endless, best {}", save.best_endless) } else { "endless".to_string() }));
+	entries.push((MenuEntry::Daily, if streak > 0 { format!("daily, streak {}", streak) } else { "daily".to_string() }));
+	entries.push((MenuEntry::Stats, "stats".to_string()));
+	entries.push((MenuEntry::Achievements, "achievements".to_string()));
+	entries.push((MenuEntry::Editor, "editor".to_string()));
+	entries.push((MenuEntry::ImportCode, "import code".to_string()));
+	entries.push((MenuEntry::ExportCode, "export code".to_string()));
+	if levelmanager.get_packs().len() > 1{
+		entries.push((MenuEntry::Pack, levelmanager.get_pack().name().chars().take(MAX_PACK_LABEL).collect()));
+	}
+	entries
 }
 
 impl Scene for MenuScene {
 	fn update(&mut self, ctx: &mut Context) -> tetra::Result<Transition> {
 		self.tween_logo.update();
-		
-		if input::is_mouse_button_released(ctx, MouseButton::Left) || input::is_key_released(ctx, Key::Return) || input::is_key_released(ctx, Key::Space) {
-			Ok(Transition::Push(Box::new(GameScene::new(ctx, Rc::clone(&self.config), Rc::clone(&self.assets))?)))
-		}else if input::is_key_released(ctx, Key::Escape) || input::is_key_released(ctx, Key::Backspace){
-			Ok(Transition::Quit)
-		}else{
-			Ok(Transition::None)
+		self.message_timer.update();
+		self.refresh_menu();
+
+		match self.menu.update(ctx){
+			Some(MenuEntry::Continue) => {
+				let level = self.save.borrow().highest_unlocked();
+				self.start_game(ctx, level)
+			}
+			Some(MenuEntry::NewGame) => self.start_game(ctx, 1),
+			Some(MenuEntry::LevelSelect) => Ok(Transition::Push(Box::new(LevelSelectScene::new(ctx, Rc::clone(&self.config), Rc::clone(&self.assets), Rc::clone(&self.save), self.levelmanager.get_shared_packs())?))),
+			Some(MenuEntry::TimeAttack) => Ok(Transition::Push(Box::new(GameScene::time_attack(ctx, Rc::clone(&self.config), Rc::clone(&self.assets), Rc::clone(&self.save), self.levelmanager.get_shared_packs())?))),
+			Some(MenuEntry::Endless) => Ok(Transition::Push(Box::new(GameScene::endless(ctx, Rc::clone(&self.config), Rc::clone(&self.assets), Rc::clone(&self.save))?))),
+			Some(MenuEntry::Daily) => self.start_daily(ctx),
+			Some(MenuEntry::Stats) => Ok(Transition::Push(Box::new(StatsScene::new(ctx, Rc::clone(&self.config), Rc::clone(&self.assets), Rc::clone(&self.save), self.levelmanager.get_shared_packs())?))),
+			Some(MenuEntry::Achievements) => Ok(Transition::Push(Box::new(AchievementsScene::new(ctx, Rc::clone(&self.config), Rc::clone(&self.assets), Rc::clone(&self.save))?))),
+			Some(MenuEntry::Editor) => {
+				let level = Level::blank(self.levelmanager.get_levels().len() as u8 + 1, DEFAULT_SIZE, DEFAULT_SIZE);
+				Ok(Transition::Push(Box::new(EditorScene::new(ctx, Rc::clone(&self.config), Rc::clone(&self.assets), Rc::clone(&self.save), level)?)))
+			}
+			Some(MenuEntry::ImportCode) => self.import_code(ctx),
+			Some(MenuEntry::ExportCode) => {
+				self.export_code(ctx);
+				Ok(Transition::None)
+			}
+			Some(MenuEntry::Pack) => {
+				self.next_pack(ctx)?;
+				Ok(Transition::None)
+			}
+			None if input::is_key_released(ctx, Key::Escape) || input::is_key_released(ctx, Key::Backspace) => Ok(Transition::Quit),
+			None => Ok(Transition::None),
 		}
 	}
 
 	fn draw(&mut self, ctx: &mut Context, _dt: f64) -> tetra::Result<Transition> {
 		graphics::clear(ctx, self.config.clear_color);
 		graphics::draw(ctx, self.assets.borrow().get_texture(&TextureName::Logo), self.tween_logo.get_position());
+		self.menu.draw(ctx);
+		if !self.message_timer.finished{
+			graphics::draw(ctx, &self.message, GET_MENU_MESSAGE_POSITION());
+		}
+		self.mouse.draw(ctx, DrawParams::default());
 
 		Ok(Transition::None)
 	}
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuEntry {
+	Continue,
+	NewGame,
+	LevelSelect,
+	TimeAttack,
+	// generated boards until the first loss
+	Endless,
+	// a generated puzzle that changes every day
+	Daily,
+	Stats,
+	Achievements,
+	Editor,
+	// levels shared as text through the clipboard
+	ImportCode,
+	ExportCode,
+	// cycles through the installed level packs
+	Pack,
+}
@@ -1,4 +1,9 @@
 pub mod manager;
 pub mod game;
 pub mod title;
-pub mod menu;
\ No newline at end of file
+pub mod menu;
+pub mod replay;
+pub mod level_select;
+pub mod editor;
+pub mod stats;
+pub mod achievements;
\ No newline at end of file
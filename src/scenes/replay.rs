@@ -0,0 +1,102 @@
+use std::rc::Rc;
+use std::cell::{RefCell};
+use std::time::Instant;
+
+use tetra::graphics::{self, Vec2};
+use tetra::input::{self, Key};
+use tetra::{Context};
+
+use crate::scenes::manager::{Scene, Transition};
+use crate::assets::{Assets, AnimationName};
+use crate::models::config::Config;
+use crate::models::replay::{Replay, ReplayAction};
+use crate::constants::*;
+use crate::managers::tilemanager::Tilemanager;
+use crate::managers::levelmanager::{self, Levelmanager};
+use crate::tile::TileState;
+
+// plays a recorded attempt back through the game's tiles
+pub struct ReplayScene {
+	config: Rc<Config>,
+	assets: Rc<RefCell<Assets>>,
+	tilemanager: Tilemanager,
+	replay: Replay,
+	next_event: usize,
+	start: Instant,
+}
+
+impl ReplayScene {
+	pub fn new(_ctx: &mut Context, config: Rc<Config>, assets: Rc<RefCell<Assets>>, replay: Replay) -> tetra::Result<ReplayScene> {
+		let mut levelmanager = Levelmanager::new(Rc::new(levelmanager::load_packs()));
+		let mut tilemanager = Tilemanager::new(Rc::clone(&assets), config.future_step_length)?;
+		if !(levelmanager.select_pack(&replay.pack) && levelmanager.select_level(replay.level) && replay.matches(levelmanager.get_current_level())){
+			log::warn!("replay is for level {} \"{}\" of \"{}\" which doesn't exist", replay.level, replay.name, replay.pack);
+		}else if let Some(event) = replay.out_of_bounds(levelmanager.get_current_level().tiles()){
+			log::warn!("replay has a {:?} at {} ms outside of the board, it won't play", event.action, event.time);
+		}else{
+			tilemanager.init_level(levelmanager.get_current_level())?;
+		}
+		tilemanager.set_input_enabled(false);
+		Ok(ReplayScene {
+			config,
+			assets,
+			tilemanager,
+			replay,
+			next_event: 0,
+			start: Instant::now(),
+		})
+	}
+
+	fn play(&mut self, action: ReplayAction){
+		match action{
+			ReplayAction::Mark(index, state) => {
+				if self.tilemanager.get_board().marks[index] != state{
+					self.tilemanager.click(index, state);
+				}
+			}
+			ReplayAction::Unmark(index) => {
+				let state = self.tilemanager.get_board().marks[index];
+				if state != TileState::Normal{
+					self.tilemanager.click(index, state);
+				}
+			}
+			ReplayAction::Undo => { self.tilemanager.undo(); },
+			ReplayAction::Redo => { self.tilemanager.redo(); },
+			ReplayAction::Future => { self.tilemanager.go_future(); },
+		}
+	}
+}
+
+impl Scene for ReplayScene {
+	fn update(&mut self, ctx: &mut Context) -> tetra::Result<Transition> {
+		self.assets.borrow_mut().update();
+		self.tilemanager.update(ctx);
+
+		let elapsed = self.start.elapsed().as_millis() as u64;
+		while self.tilemanager.get_board().len() > 0 && self.next_event < self.replay.events.len() && self.replay.events[self.next_event].time <= elapsed{
+			let action = self.replay.events[self.next_event].action;
+			self.next_event +=1;
+			self.play(action);
+		}
+
+		if input::is_key_pressed(ctx, Key::Space){
+			self.tilemanager.skip();
+		}
+
+		if input::is_key_released(ctx, Key::Backspace) || input::is_key_released(ctx, Key::Escape){
+			Ok(Transition::Pop)
+		}else{
+			Ok(Transition::None)
+		}
+	}
+
+	fn draw(&mut self, ctx: &mut Context, _dt: f64) -> tetra::Result<Transition> {
+		graphics::clear(ctx, self.config.clear_color);
+		self.tilemanager.draw(ctx);
+		for i in 0..self.tilemanager.actions(){
+			graphics::draw(ctx,self.assets.borrow().get_animation(&AnimationName::Action),
+				Vec2::new(f32::from(i*10 + X_POSITION_MOVES_SYMBOLE), f32::from(Y_POSITION_MOVES_SYMBOLE)));
+		}
+		Ok(Transition::None)
+	}
+}
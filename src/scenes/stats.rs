@@ -0,0 +1,109 @@
+use std::rc::Rc;
+use std::cell::{RefCell};
+
+use tetra::{Context, input};
+use tetra::input::{Key};
+use tetra::graphics::{self, Drawable, DrawParams};
+use tetra::graphics::text::{Text, Font};
+
+use crate::assets::Assets;
+use crate::scenes::manager::{Scene, Transition};
+use crate::managers::levelmanager::Levelmanager;
+use crate::models::config::Config;
+use crate::models::save::SaveGame;
+use crate::models::pack::Pack;
+use crate::models::stats::{self, LevelStats, CSV_FILE};
+use crate::models::time_attack::format_time;
+use crate::constants::*;
+use crate::pager::Pager;
+use crate::utils::mouse::Mouse;
+use crate::utils::storage;
+
+// what was played on each level of the current pack, one level at a time
+pub struct StatsScene {
+	config: Rc<Config>,
+	assets: Rc<RefCell<Assets>>,
+	save: Rc<RefCell<SaveGame>>,
+	levelmanager: Levelmanager,
+	pager: Pager,
+	info: Text,
+	mouse: Mouse,
+}
+
+impl StatsScene {
+	pub fn new(_ctx: &mut Context,config: Rc<Config>, assets: Rc<RefCell<Assets>>, save: Rc<RefCell<SaveGame>>, packs: Rc<Vec<Pack>>) -> tetra::Result<StatsScene> {
+		let mut levelmanager = Levelmanager::new(packs);
+		levelmanager.select_pack(&save.borrow().pack);
+		let mut scene = StatsScene {
+			mouse: Mouse::new(Rc::clone(&assets))?,
+			config,
+			assets,
+			save,
+			pager: Pager::new(levelmanager.get_levels().len()),
+			levelmanager,
+			info: Text::new("x exports csv", Font::default(), 8.0),
+		};
+		scene.select(0);
+		Ok(scene)
+	}
+
+	fn select(&mut self, index: usize){
+		let level = &self.levelmanager.get_levels()[index];
+		let stats = self.save.borrow().progress(level.number).map_or(LevelStats::default(), |p|p.stats);
+		let details = format!("attempts {}\nlosses {}{}\ntime {}\nmarks {}\nhints {}",
+			stats.attempts,
+			stats.losses,
+			stats.loss_rate().map_or(String::new(), |rate|format!(" ({}%)", rate)),
+			format_time(stats.milliseconds),
+			stats.marks,
+			stats.hints);
+		self.pager.set_page(&format!("{} {}", level.number, level.name), &details, false);
+	}
+
+	// every pack goes into the same file
+	fn export(&mut self){
+		let path = match storage::data_dir(){
+			Some(directory) => directory.join(CSV_FILE),
+			None => return,
+		};
+		let packs = self.levelmanager.get_packs().iter().map(|p|(p.name(), &p.levels[..])).collect::<Vec<_>>();
+		let csv = stats::to_csv(&self.save.borrow(), &packs);
+		match storage::write_atomic(&path, &csv){
+			Ok(_) => {
+				log::info!("stats exported to {}", path.display());
+				self.info.set_content("exported");
+			}
+			Err(error) => {
+				log::warn!("can't export stats to {}: {}", path.display(), error);
+				self.info.set_content("can't export");
+			}
+		}
+	}
+}
+
+impl Scene for StatsScene {
+	fn update(&mut self, ctx: &mut Context) -> tetra::Result<Transition> {
+		self.assets.borrow_mut().update();
+		if let Some(index) = self.pager.update(ctx){
+			self.select(index);
+		}
+		if input::is_key_released(ctx, Key::X){
+			self.export();
+		}
+
+		if input::is_key_released(ctx, Key::Escape) || input::is_key_released(ctx, Key::Backspace){
+			Ok(Transition::Pop)
+		}else{
+			Ok(Transition::None)
+		}
+	}
+
+	fn draw(&mut self, ctx: &mut Context, _dt: f64) -> tetra::Result<Transition> {
+		graphics::clear(ctx, self.config.clear_color);
+		self.pager.draw(ctx);
+		graphics::draw(ctx, &self.info, GET_LEVEL_INFO_POSITION());
+		self.mouse.draw(ctx, DrawParams::default());
+
+		Ok(Transition::None)
+	}
+}
@@ -2,14 +2,12 @@ use std::rc::Rc;
 use std::cell::{RefCell};
 use serde::{Serialize, Deserialize};
 
-use tetra::graphics::{Drawable, DrawParams, Vec2, Rectangle};
+use tetra::graphics::{Drawable, DrawParams, Vec2, Rectangle, Color};
 use tetra::input::{self, MouseButton};
 use tetra::{Context, glm};
 use crate::assets::{Assets, TextureName, AnimationName};
 use crate::utils::Disabled;
 
-const MAXNUMBER: u8 = 4;
-
 pub struct Tile{
 	assets: Rc<RefCell<Assets>>,
 	pub position: Vec2,
@@ -18,6 +16,10 @@ pub struct Tile{
 	pressed: bool,
 	pub number: u8,
 	disabled: Disabled,
+	scale: Vec2,
+	wall: bool,
+	// the editor clicks every tile
+	editing: bool,
 }
 
 #[allow(dead_code)]
@@ -31,29 +33,22 @@ impl Tile {
 			pressed: false,
 			number,
 			disabled: Disabled::Off,
+			scale: Vec2::new(1.0,1.0),
+			wall: false,
+			editing: false,
 		})
 	}
 
-	pub fn number(&mut self, number: u8){
-		self.number = number;
+	pub fn set_scale(self, scale: f32) -> Self{
+		Tile { scale: Vec2::new(scale,scale), ..self}
 	}
 
-	pub fn subtract(&mut self){
-		self.state = TileState::Normal;
-		if self.number > 1{
-			self.number -=1;
-		}else{
-			self.number = MAXNUMBER;
-		}
+	pub fn set_editing(self, editing: bool) -> Self{
+		Tile { editing, ..self}
 	}
-	
-	pub fn add_up(&mut self){
-		self.state = TileState::Normal;
-		if self.number < MAXNUMBER{
-			self.number +=1;
-		}else{
-			self.number = 1;
-		}
+
+	pub fn number(&mut self, number: u8){
+		self.number = number;
 	}
 
 	pub fn is_marked(&self) -> bool{
@@ -64,14 +59,10 @@ impl Tile {
 		self.state = state
 	}
 	
-	pub fn go_future(&mut self){
-		match self.state{
-			TileState::Plus => self.add_up(),
-			TileState::Minus => self.subtract(),
-			_ => ()
-		}
+	pub fn set_wall(&mut self, wall: bool){
+		self.wall = wall;
 	}
-	
+
 	pub fn set_enabled(&mut self, enabled: bool){
 		if enabled { self.enable() } else { self.disable() }
 	}
@@ -84,26 +75,19 @@ impl Tile {
 		self.disabled = Disabled::Off;
 	}
 
-	pub fn update(&mut self, ctx: &mut Context){
-		if !bool::from(self.disabled) && self.number > 0 {
+	// returns the mark the player clicked, the board decides what it does
+	pub fn update(&mut self, ctx: &mut Context) -> Option<TileState>{
+		let mut clicked = None;
+		if self.editing || (!self.wall && !bool::from(self.disabled) && self.number > 0) {
 			let mouse_position = glm::round(&input::get_mouse_position(ctx));
 			if is_inside_hover_area(self.position, self.touch_area, mouse_position) {
 				if input::is_mouse_button_down(ctx, MouseButton::Left) && !self.pressed{
 					self.pressed = true;
-					if self.state != TileState::Plus{
-						self.state = TileState::Plus;
-						
-					}else{
-						self.state = TileState::Normal;
-					}
+					clicked = Some(TileState::Plus);
 				}
 				if input::is_mouse_button_down(ctx, MouseButton::Right) && !self.pressed{
 					self.pressed = true;
-					if self.state != TileState::Minus{
-						self.state = TileState::Minus;
-					}else{
-						self.state = TileState::Normal;
-					}
+					clicked = Some(TileState::Minus);
 				}
 				if input::is_mouse_button_released(ctx, MouseButton::Left) ||
 					input::is_mouse_button_released(ctx, MouseButton::Right){
@@ -111,6 +95,7 @@ impl Tile {
 				}
 			}
 		}
+		clicked
 	}
 
 	pub fn get_animation_name(&self) -> Option<AnimationName>{
@@ -122,7 +107,14 @@ impl Tile {
 	}
 
 	pub fn get_texture_name(&self) -> TextureName{
-		TextureName::from_str(&format!("Pic{}{:?}", self.number, !self.disabled))
+		if self.wall{
+			return TextureName::Wall;
+		}
+		TextureName::for_value(self.number, !bool::from(self.disabled)).0
+	}
+
+	pub fn get_tint(&self) -> Color{
+		TextureName::for_value(self.number, !bool::from(self.disabled)).1
 	}
 
 	pub fn is_pressed(&self) -> bool{
@@ -147,12 +139,14 @@ impl Drawable for Tile {
 		let texture_name = self.get_texture_name();
 		self.assets.borrow().get_texture(&texture_name).draw(ctx,DrawParams::new()
 			.position(Vec2::new(self.position.x,self.position.y))
+			.scale(self.scale)
+			.color(self.get_tint())
 		);
 		//foreground
 		let animation_name = self.get_animation_name();
 		if let Some(a) = animation_name{
 			self.assets.borrow().get_animation(&a).draw(
-			ctx,DrawParams::new().position(Vec2::new(self.position.x,self.position.y))) 
+			ctx,DrawParams::new().position(Vec2::new(self.position.x,self.position.y)).scale(self.scale))
 		}
 	}
 }
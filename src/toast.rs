@@ -0,0 +1,63 @@
+use std::rc::Rc;
+use std::cell::{RefCell};
+use std::collections::VecDeque;
+
+use tetra::graphics::{self, DrawParams, Vec2, Color};
+use tetra::graphics::text::{Text, Font};
+use tetra::Context;
+
+use crate::assets::{Assets, TextureName};
+use crate::constants::*;
+use crate::utils::timer::Timer;
+
+// the black texture is as big as the window
+const STRIP_HEIGHT: f32 = 12.0;
+
+// a short message over the top of the screen, later ones wait for their turn
+pub struct Toast{
+	assets: Rc<RefCell<Assets>>,
+	text: Text,
+	queue: VecDeque<String>,
+	timer: Timer,
+}
+
+#[allow(dead_code)]
+impl Toast{
+	pub fn new(assets: Rc<RefCell<Assets>>) -> Toast{
+		let mut timer = Timer::new(2);
+		timer.finished = true;
+		Toast{
+			assets,
+			text: Text::new("", Font::default(), 8.0),
+			queue: VecDeque::new(),
+			timer,
+		}
+	}
+
+	pub fn show(&mut self, message: String){
+		self.queue.push_back(message);
+	}
+
+	pub fn update(&mut self){
+		self.timer.update();
+		if self.timer.finished{
+			if let Some(message) = self.queue.pop_front(){
+				self.text.set_content(message);
+				self.timer.restart();
+			}
+		}
+	}
+
+	pub fn draw(&self, ctx: &mut Context){
+		if self.timer.finished{
+			return;
+		}
+		let position = GET_TOAST_POSITION();
+		graphics::draw(ctx, self.assets.borrow().get_texture(&TextureName::Black), DrawParams::new()
+			.position(position)
+			.scale(Vec2::new(1.0, STRIP_HEIGHT / 100.0))
+			.color(Color::rgba(1.0, 1.0, 1.0, 0.8))
+			);
+		graphics::draw(ctx, &self.text, position + Vec2::new(4.0, 2.0));
+	}
+}
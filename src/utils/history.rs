@@ -0,0 +1,100 @@
+// undo and redo by keeping whole snapshots, the states we store are small
+pub struct History<T>{
+	undo: Vec<T>,
+	redo: Vec<T>,
+}
+
+#[allow(dead_code)]
+impl<T> History<T>{
+	pub fn new() -> History<T>{
+		History{
+			undo: vec![],
+			redo: vec![],
+		}
+	}
+
+	// call with the state from before a change
+	pub fn record(&mut self, state: T){
+		self.undo.push(state);
+		self.redo.clear();
+	}
+
+	pub fn undo(&mut self, current: T) -> Option<T>{
+		let state = self.undo.pop()?;
+		self.redo.push(current);
+		Some(state)
+	}
+
+	pub fn redo(&mut self, current: T) -> Option<T>{
+		let state = self.redo.pop()?;
+		self.undo.push(current);
+		Some(state)
+	}
+
+	pub fn can_undo(&self) -> bool{
+		!self.undo.is_empty()
+	}
+
+	pub fn can_redo(&self) -> bool{
+		!self.redo.is_empty()
+	}
+
+	pub fn clear(&mut self){
+		self.undo.clear();
+		self.redo.clear();
+	}
+}
+
+#[cfg(test)]
+mod tests{
+	use super::*;
+	use crate::models::board::Board;
+	use crate::models::level::Level;
+	use crate::tile::TileState;
+
+	#[test]
+	fn undo_and_redo_walk_the_snapshots(){
+		let mut history = History::new();
+		assert_eq!(history.undo(0), None);
+		history.record(0);
+		history.record(1);
+		assert_eq!(history.undo(2), Some(1));
+		assert_eq!(history.undo(1), Some(0));
+		assert!(!history.can_undo());
+		assert_eq!(history.redo(0), Some(1));
+		assert_eq!(history.redo(1), Some(2));
+		assert!(!history.can_redo());
+	}
+
+	#[test]
+	fn a_new_record_drops_the_redo(){
+		let mut history = History::new();
+		history.record(0);
+		assert_eq!(history.undo(1), Some(0));
+		assert!(history.can_redo());
+		history.record(0);
+		assert!(!history.can_redo());
+		history.clear();
+		assert!(!history.can_undo());
+	}
+
+	// the same steps the tilemanager takes for a click and the undo button
+	#[test]
+	fn undo_gives_the_actions_back(){
+		let mut level = Level::blank(1, 3, 3);
+		level.values[0] = 2;
+		level.states[0] = true;
+		level.moves = 1;
+		let mut board = Board::from_level(&level);
+		let mut history = History::new();
+		let before = board.clone();
+		assert!(board.toggle_mark(0, TileState::Plus));
+		history.record(before);
+		assert_eq!(board.actions, 0);
+		board = history.undo(board).unwrap();
+		assert_eq!(board.actions, 1);
+		assert!(!board.is_marked(0));
+		board = history.redo(board).unwrap();
+		assert_eq!((board.actions, board.marks[0]), (0, TileState::Plus));
+	}
+}
@@ -5,6 +5,9 @@ pub mod tween;
 pub mod particle;
 pub mod mouse;
 pub mod animation;
+pub mod history;
+pub mod storage;
+pub mod preview;
 
 
 // just for fun and learning
@@ -63,6 +63,10 @@ impl Particle{
 		Particle {texture_name, ..self}
 	}
 
+	pub fn set_color(self, color: Color) -> Self{
+		Particle {color, ..self}
+	}
+
 	pub fn is_dead(&self) -> bool{
 		self.dead
 	}
@@ -97,7 +101,7 @@ impl Particle{
 		}else{
 			1.0
 		};
-		Color::rgba(1.0,1.0,1.0, a)
+		Color::rgba(self.color.r,self.color.g,self.color.b, a)
 	}
 
 	pub fn update(&mut self){
@@ -0,0 +1,31 @@
+use tetra::graphics::{self, DrawParams, Vec2, Color};
+use tetra::Context;
+
+use crate::assets::{Assets, TextureName};
+use crate::models::board::TileKind;
+use crate::models::level::Level;
+use crate::constants::TILE_SIZE;
+
+// the level's starting board shrunk into a square of the given size
+pub fn draw_preview(ctx: &mut Context, assets: &Assets, level: &Level, position: Vec2, size: f32, color: Color){
+	let tile_size = size / level.width.max(level.height) as f32;
+	let scale = tile_size / TILE_SIZE as f32;
+	let offset = Vec2::new(
+		(size - level.width as f32 * tile_size) / 2.0,
+		(size - level.height as f32 * tile_size) / 2.0);
+	for x in 0..level.width{
+		for y in 0..level.height{
+			let i = x * level.height + y;
+			let (texture_name, tint) = if level.kinds[i] == TileKind::Wall{
+				(TextureName::Wall, Color::rgb(1.0, 1.0, 1.0))
+			}else{
+				TextureName::for_value(level.values[i], level.states[i])
+			};
+			graphics::draw(ctx, assets.get_texture(&texture_name), DrawParams::new()
+				.position(position + offset + Vec2::new(x as f32 * tile_size, y as f32 * tile_size))
+				.scale(Vec2::new(scale, scale))
+				.color(Color::rgba(tint.r * color.r, tint.g * color.g, tint.b * color.b, color.a))
+				);
+		}
+	}
+}
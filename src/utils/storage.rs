@@ -0,0 +1,124 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const GAME_DIRECTORY: &str = "will_it_dissolve";
+
+// the game's folder in the user data directory, $XDG_DATA_HOME on linux
+pub fn data_dir() -> Option<PathBuf>{
+	dirs::data_dir().map(|dir|dir.join(GAME_DIRECTORY))
+}
+
+pub fn replay_dir() -> Option<PathBuf>{
+	data_dir().map(|dir|dir.join("replays"))
+}
+
+pub fn editor_dir() -> Option<PathBuf>{
+	data_dir().map(|dir|dir.join("editor"))
+}
+
+// a directory next to the executable and one in the user data directory
+fn game_dirs(name: &str) -> Vec<PathBuf>{
+	let next_to_executable = env::current_exe().ok()
+		.and_then(|exe|exe.parent().map(|dir|dir.join(name)));
+	let user = data_dir().map(|dir|dir.join(name));
+	next_to_executable.into_iter().chain(user).collect()
+}
+
+// designers drop level files into one of these, the later one wins for equal numbers
+pub fn level_dirs() -> Vec<PathBuf>{
+	game_dirs("levels")
+}
+
+pub fn pack_dirs() -> Vec<PathBuf>{
+	game_dirs("packs")
+}
+
+fn with_extension(path: &Path, extension: &str) -> PathBuf{
+	let mut name = path.as_os_str().to_owned();
+	name.push(extension);
+	PathBuf::from(name)
+}
+
+// writes a temporary file first and keeps the previous content as .bak,
+// so a crash leaves either the old or the new file behind
+pub fn write_atomic(path: &Path, content: &str) -> io::Result<()>{
+	if let Some(parent) = path.parent(){
+		fs::create_dir_all(parent)?;
+	}
+	let temporary = with_extension(path, ".tmp");
+	{
+		let mut file = File::create(&temporary)?;
+		file.write_all(content.as_bytes())?;
+		file.sync_all()?;
+	}
+	if path.exists(){
+		fs::copy(path, with_extension(path, ".bak"))?;
+	}
+	fs::rename(&temporary, path)
+}
+
+// the file or, if it is missing or unreadable, its backup
+pub fn read_with_backup<T, F>(path: &Path, parse: F) -> Option<T>
+	where F: Fn(&str) -> Option<T>
+{
+	let read = |path: &Path| fs::read_to_string(path).ok().and_then(|content|parse(&content));
+	match read(path){
+		Some(value) => Some(value),
+		None => {
+			let backup = with_extension(path, ".bak");
+			let value = read(&backup);
+			if value.is_some(){
+				log::warn!("{} is broken, using {}", path.display(), backup.display());
+			}
+			value
+		}
+	}
+}
+
+// a fresh directory per test and process, the test removes it again at the end
+#[cfg(test)]
+pub fn scratch_dir(name: &str) -> PathBuf{
+	let dir = env::temp_dir().join(format!("{}_{}_{}", GAME_DIRECTORY, name, std::process::id()));
+	let _ = fs::remove_dir_all(&dir);
+	fs::create_dir_all(&dir).unwrap();
+	dir
+}
+
+#[cfg(test)]
+mod tests{
+	use super::*;
+
+	fn parse(content: &str) -> Option<u32>{
+		content.trim().parse().ok()
+	}
+
+	#[test]
+	fn the_previous_content_becomes_the_backup(){
+		let dir = scratch_dir("backup");
+		let path = dir.join("save.ron");
+		write_atomic(&path, "1").unwrap();
+		assert!(!with_extension(&path, ".bak").exists());
+		write_atomic(&path, "2").unwrap();
+		assert_eq!(fs::read_to_string(with_extension(&path, ".bak")).unwrap(), "1");
+		assert!(!with_extension(&path, ".tmp").exists());
+		assert_eq!(read_with_backup(&path, parse), Some(2));
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn a_broken_file_falls_back_to_the_backup(){
+		let dir = scratch_dir("broken");
+		let path = dir.join("save.ron");
+		write_atomic(&path, "1").unwrap();
+		write_atomic(&path, "2").unwrap();
+		fs::write(&path, "not a number").unwrap();
+		assert_eq!(read_with_backup(&path, parse), Some(1));
+		fs::remove_file(&path).unwrap();
+		assert_eq!(read_with_backup(&path, parse), Some(1));
+		fs::remove_file(with_extension(&path, ".bak")).unwrap();
+		assert_eq!(read_with_backup(&path, parse), None);
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
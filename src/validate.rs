@@ -0,0 +1,40 @@
+use crate::managers::levelmanager::level_files;
+use crate::models::level::parse_level;
+use crate::models::solver::solve;
+
+// checks every level without opening a window, returns the exit code
+pub fn run() -> i32{
+	let files = level_files();
+	let mut failed = 0;
+	for (file, content) in files.iter(){
+		let level = match parse_level(content){
+			Ok(level) => level,
+			Err(error) => {
+				println!("{}: FAILED", file);
+				println!("\tcan't parse: {}", error);
+				failed +=1;
+				continue;
+			}
+		};
+		let mut errors = level.check();
+		let mut summary = String::new();
+		if errors.is_empty(){
+			let report = solve(&level);
+			match report.min_marks(){
+				Some(min) => summary = format!("min marks {}, {} solutions", min, report.solutions.len()),
+				None => errors.push(format!("can't be solved within {} moves", level.moves)),
+			}
+		}
+		if errors.is_empty(){
+			println!("{}: #{} \"{}\" ok ({})", file, level.number, level.name, summary);
+		}else{
+			println!("{}: #{} \"{}\" FAILED", file, level.number, level.name);
+			for error in errors{
+				println!("\t{}", error);
+			}
+			failed +=1;
+		}
+	}
+	println!("{} levels checked, {} failed", files.len(), failed);
+	if failed > 0 { 1 } else { 0 }
+}